@@ -0,0 +1,386 @@
+//! Cache of responses from read-only API endpoints.
+//!
+//! Successful `GET` responses of list and metadata endpoints are kept for a
+//! per-endpoint time to live, keyed by URL and account. Once an entry is
+//! stale, it is revalidated with `If-None-Match` / `If-Modified-Since` when
+//! the server sent an `ETag` or `Last-Modified`, so an unchanged result costs
+//! a `304` rather than a full response. A successful `POST` drops the
+//! account's entries under the same top-level endpoint (e.g. `/kernels/push`
+//! drops `/kernels/...`).
+//!
+//! The `cache` setting selects `memory` (the default), `disk` (entries also
+//! survive restarts, in `cache/` in the config directory) or `off`. Kernel
+//! pulls, which may hold private source code, are only cached in memory,
+//! and the disk cache keeps at most as many files as the memory cache.
+
+use super::write_private_file;
+use crate::models::{CacheMode, Error, KaggleAuth};
+use reqwest::header::{CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Most entries kept in memory, and files kept on disk; the oldest is
+/// dropped beyond this.
+const MAX_ENTRIES: usize = 256;
+
+/// Largest response body that is cached.
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Returns how long a response of an endpoint (a path relative to the API
+/// base, without the query) stays fresh, or `None` if it is not cached.
+fn ttl(endpoint: &str) -> Option<Duration> {
+    const MINUTE: Duration = Duration::from_secs(60);
+    if endpoint.contains("/download") {
+        return None;
+    }
+    match endpoint {
+        "/competitions/list" | "/models/list" => Some(5 * MINUTE),
+        "/kernels/pull" | "/kernels/versions/list" => Some(MINUTE),
+        _ if endpoint.starts_with("/models/") && endpoint.ends_with("/get") => Some(5 * MINUTE),
+        _ if endpoint.starts_with("/models/") && endpoint.ends_with("/versions/list") => Some(MINUTE),
+        _ => None,
+    }
+}
+
+/// Returns whether responses of an endpoint may be written to disk. Kernel
+/// sources may be private, so they stay in memory.
+fn persistent(endpoint: &str) -> bool {
+    endpoint != "/kernels/pull"
+}
+
+/// A cached response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedResponse {
+    account: String,
+    url: String,
+    content_type: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    stored_at: SystemTime,
+    ttl: Duration,
+    body: String,
+}
+
+impl CachedResponse {
+    fn is_fresh(&self) -> bool {
+        self.stored_at.elapsed().is_ok_and(|age| age < self.ttl)
+    }
+
+    fn to_response(&self) -> Response {
+        let mut builder = http::Response::builder().status(StatusCode::OK);
+        if let Some(ref content_type) = self.content_type {
+            builder = builder.header(CONTENT_TYPE, content_type);
+        }
+        builder
+            .body(self.body.clone())
+            .expect("cached response headers are valid")
+            .into()
+    }
+}
+
+/// Identifies a cacheable request.
+#[derive(Debug, Clone)]
+pub(crate) struct CacheKey {
+    account: String,
+    url: String,
+    ttl: Duration,
+    persistent: bool,
+}
+
+impl CacheKey {
+    fn id(&self) -> String {
+        let digest = Sha256::digest(format!("{}\n{}", self.account, self.url));
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// What the cache holds for a request.
+pub(crate) enum Lookup {
+    /// A fresh response, to be used without asking the server
+    Fresh(Response),
+    /// A stale response; the request was given its validators
+    Stale,
+    /// Nothing usable
+    Miss,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    mode: CacheMode,
+    disk_dir: Option<PathBuf>,
+    entries: HashMap<String, CachedResponse>,
+}
+
+/// Response cache shared by all tool calls.
+#[derive(Debug, Default)]
+pub(crate) struct ResponseCache {
+    state: Mutex<CacheState>,
+}
+
+impl ResponseCache {
+    /// Selects where entries are kept. Switching modes empties the memory
+    /// cache.
+    pub(crate) async fn configure(&self, mode: CacheMode, disk_dir: Option<PathBuf>) {
+        let mut state = self.state.lock().await;
+        if state.mode != mode {
+            state.entries.clear();
+        }
+        state.mode = mode;
+        state.disk_dir = disk_dir;
+    }
+
+    /// Returns the cache key of a request, or `None` if its response is not
+    /// cached.
+    pub(crate) async fn key(
+        &self,
+        request: &Request,
+        api_base: &str,
+        credentials: &KaggleAuth,
+    ) -> Option<CacheKey> {
+        if request.method() != reqwest::Method::GET || self.state.lock().await.mode == CacheMode::Off {
+            return None;
+        }
+        let url = request.url().as_str();
+        let endpoint = url.strip_prefix(api_base)?.split('?').next()?;
+        Some(CacheKey {
+            account: account(credentials),
+            url: url.to_string(),
+            ttl: ttl(endpoint)?,
+            persistent: persistent(endpoint),
+        })
+    }
+
+    /// Looks up a request. For a stale entry, `request` gets the
+    /// conditional headers to revalidate it with.
+    pub(crate) async fn lookup(&self, key: &CacheKey, request: &mut Request) -> Lookup {
+        let mut state = self.state.lock().await;
+        let id = key.id();
+        if !state.entries.contains_key(&id) && key.persistent {
+            if let Some(entry) = read_disk_entry(&state, &id).await {
+                state.entries.insert(id.clone(), entry);
+            }
+        }
+        let Some(entry) = state.entries.get(&id).filter(|entry| entry.url == key.url) else {
+            return Lookup::Miss;
+        };
+        if entry.is_fresh() {
+            debug!("Serving {} from the cache", key.url);
+            return Lookup::Fresh(entry.to_response());
+        }
+
+        let headers = request.headers_mut();
+        let mut revalidate = false;
+        if let Some(value) = entry.etag.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_NONE_MATCH, value);
+            revalidate = true;
+        }
+        if let Some(value) = entry.last_modified.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_MODIFIED_SINCE, value);
+            revalidate = true;
+        }
+        if revalidate {
+            Lookup::Stale
+        } else {
+            Lookup::Miss
+        }
+    }
+
+    /// Marks a stale entry fresh again after the server answered `304 Not
+    /// Modified`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the cached response, or `None` if the entry is gone.
+    pub(crate) async fn revalidated(&self, key: &CacheKey) -> Option<Response> {
+        let mut state = self.state.lock().await;
+        let id = key.id();
+        let entry = state.entries.get_mut(&id)?;
+        entry.stored_at = SystemTime::now();
+        entry.ttl = key.ttl;
+        let entry = entry.clone();
+        debug!("{} is unchanged", key.url);
+        if key.persistent {
+            write_disk_entry(&state, &id, &entry).await;
+        }
+        Some(entry.to_response())
+    }
+
+    /// Caches a successful response.
+    ///
+    /// # Returns
+    ///
+    /// Returns the response to hand to the caller in place of the one that
+    /// was read.
+    pub(crate) async fn store(&self, key: &CacheKey, response: Response) -> Result<Response, Error> {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let (content_type, etag, last_modified) = (header(CONTENT_TYPE), header(ETAG), header(LAST_MODIFIED));
+        let too_large = response
+            .content_length()
+            .is_some_and(|length| length > MAX_BODY_BYTES as u64);
+        if response.status() != StatusCode::OK || too_large {
+            return Ok(response);
+        }
+
+        let body = response.bytes().await?;
+        let Ok(body) = String::from_utf8(body.to_vec()) else {
+            let mut response = http::Response::new(body);
+            if let Some(value) = content_type.and_then(|v| v.parse().ok()) {
+                response.headers_mut().insert(CONTENT_TYPE, value);
+            }
+            return Ok(response.into());
+        };
+        let entry = CachedResponse {
+            account: key.account.clone(),
+            url: key.url.clone(),
+            content_type,
+            etag,
+            last_modified,
+            stored_at: SystemTime::now(),
+            ttl: key.ttl,
+            body,
+        };
+        let response = entry.to_response();
+        if entry.body.len() > MAX_BODY_BYTES {
+            return Ok(response);
+        }
+
+        let mut state = self.state.lock().await;
+        if state.entries.len() >= MAX_ENTRIES {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored_at)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        let id = key.id();
+        if key.persistent {
+            write_disk_entry(&state, &id, &entry).await;
+        }
+        state.entries.insert(id, entry);
+        Ok(response)
+    }
+
+    /// Drops an account's entries under the same top-level endpoint as `url`
+    /// (e.g. every `/models/...` entry for `/models/owner/slug/update`),
+    /// after a call to `url` that may have changed them.
+    pub(crate) async fn invalidate(&self, url: &str, api_base: &str, credentials: &KaggleAuth) {
+        let Some(endpoint) = url.strip_prefix(api_base) else {
+            return;
+        };
+        let resource = endpoint.trim_start_matches('/').split(['/', '?']).next().unwrap_or_default();
+        let prefix = format!("{}/{}", api_base, resource);
+        let account = account(credentials);
+        let mut state = self.state.lock().await;
+        let matches = |entry: &CachedResponse| entry.account == account && entry.url.starts_with(&prefix);
+        state.entries.retain(|_, entry| !matches(entry));
+
+        let Some(ref dir) = state.disk_dir else {
+            return;
+        };
+        let Ok(mut files) = tokio::fs::read_dir(dir).await else {
+            return;
+        };
+        while let Ok(Some(file)) = files.next_entry().await {
+            let Ok(content) = tokio::fs::read_to_string(file.path()).await else {
+                continue;
+            };
+            if serde_json::from_str::<CachedResponse>(&content).is_ok_and(|entry| matches(&entry)) {
+                let _ = tokio::fs::remove_file(file.path()).await;
+            }
+        }
+        debug!("Invalidated cached responses under {}", prefix);
+    }
+}
+
+#[cfg(test)]
+impl ResponseCache {
+    /// Makes every entry in memory stale.
+    pub(crate) async fn expire(&self) {
+        for entry in self.state.lock().await.entries.values_mut() {
+            entry.ttl = Duration::ZERO;
+        }
+    }
+
+    /// Drops every entry in memory, from outside the async runtime.
+    pub(crate) fn clear(&self) {
+        self.state.try_lock().expect("cache is not in use").entries.clear();
+    }
+}
+
+/// Identifies the account a request is made as, without exposing its key.
+fn account(credentials: &KaggleAuth) -> String {
+    match credentials.username() {
+        Some(username) => username.to_string(),
+        None => {
+            let digest = Sha256::digest(credentials.secret());
+            let hex: String = digest.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+            format!("token-{}", hex)
+        }
+    }
+}
+
+/// Reads an entry from the disk cache, if it is enabled and has one.
+async fn read_disk_entry(state: &CacheState, id: &str) -> Option<CachedResponse> {
+    if state.mode != CacheMode::Disk {
+        return None;
+    }
+    let path = state.disk_dir.as_ref()?.join(id);
+    let content = tokio::fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Writes an entry to the disk cache, if it is enabled, and drops the
+/// oldest files beyond `MAX_ENTRIES`. Failures are only logged, as the
+/// memory cache still works.
+async fn write_disk_entry(state: &CacheState, id: &str, entry: &CachedResponse) {
+    if state.mode != CacheMode::Disk {
+        return;
+    }
+    let Some(ref dir) = state.disk_dir else {
+        return;
+    };
+    let result = match serde_json::to_string(entry) {
+        Ok(content) => write_private_file(&dir.join(id), &content).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
+        warn!("Could not write the response cache: {}", e);
+        return;
+    }
+
+    let Ok(mut files) = tokio::fs::read_dir(dir).await else {
+        return;
+    };
+    let mut written = Vec::new();
+    while let Ok(Some(file)) = files.next_entry().await {
+        // Skip files being written, which start with a dot.
+        if file.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if let Ok(modified) = file.metadata().await.and_then(|metadata| metadata.modified()) {
+            written.push((modified, file.path()));
+        }
+    }
+    if written.len() > MAX_ENTRIES {
+        written.sort();
+        for (_, path) in &written[..written.len() - MAX_ENTRIES] {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        debug!("Pruned {} files from the response cache", written.len() - MAX_ENTRIES);
+    }
+}
@@ -0,0 +1,132 @@
+//! Competition endpoints.
+
+use super::KaggleClient;
+use crate::models::{
+    CodeSubmissionRequest, CompetitionRef, Error, KernelRef, KernelVersion, SubmissionResponse,
+};
+use tracing::{debug, info};
+
+/// Page size used when searching a kernel's version history.
+const VERSION_PAGE_SIZE: i32 = 100;
+
+/// Most pages of a kernel's version history searched for a version.
+const MAX_VERSION_PAGES: i32 = 20;
+
+impl KaggleClient {
+    /// Submits the output of a kernel version to a code competition.
+    ///
+    /// Before submitting, this checks that the kernel version is attached to
+    /// the competition and that it completed successfully, so obviously
+    /// invalid submissions fail without using up a daily submission. The
+    /// attachment is checked against the data sources Kaggle returns when
+    /// pulling that version; if it returns another version's metadata, the
+    /// submission is refused as the check cannot be made.
+    ///
+    /// # Arguments
+    ///
+    /// * `competition` - The competition to submit to
+    /// * `kernel` - The kernel whose output should be submitted
+    /// * `version` - Kernel version whose output should be submitted
+    /// * `file_name` - Output file to submit (e.g., "submission.csv")
+    /// * `message` - Submission description
+    ///
+    /// # Returns
+    ///
+    /// Returns the submission response, or `Error::InvalidParameter` if the
+    /// kernel version is not attached to the competition (or that cannot be
+    /// confirmed) or did not complete.
+    pub async fn submit_kernel_version(
+        &self,
+        competition: &CompetitionRef,
+        kernel: &KernelRef,
+        version: i32,
+        file_name: &str,
+        message: &str,
+    ) -> Result<SubmissionResponse, Error> {
+        let pulled = self.pull_kernel_version(kernel, Some(version)).await?;
+        // The data sources can only be trusted if they are those of the
+        // requested version.
+        if pulled.metadata.current_version_number != Some(version) {
+            return Err(Error::InvalidParameter(format!(
+                "Cannot check that kernel {} v{} is attached to competition {}: Kaggle returned \
+                 the metadata of version {}",
+                kernel,
+                version,
+                competition,
+                pulled
+                    .metadata
+                    .current_version_number
+                    .map_or_else(|| "unknown".to_string(), |n| n.to_string())
+            )));
+        }
+        if !pulled.metadata.competition_data_sources.contains(&competition.slug) {
+            return Err(Error::InvalidParameter(format!(
+                "Kernel {} v{} is not attached to competition {}",
+                kernel, version, competition
+            )));
+        }
+
+        let kernel_version = self.find_kernel_version(kernel, version).await?;
+        match kernel_version.status.as_deref() {
+            Some(status) if status.eq_ignore_ascii_case("complete") => {}
+            status => {
+                return Err(Error::InvalidParameter(format!(
+                    "Kernel {} v{} has not completed successfully (status: {})",
+                    kernel,
+                    version,
+                    status.unwrap_or("unknown")
+                )))
+            }
+        }
+
+        let url = self.endpoint(&format!(
+            "/competitions/submissions/submit-notebook/{}",
+            urlencoding::encode(&competition.slug)
+        ));
+        let request = CodeSubmissionRequest {
+            competition_name: competition.slug.clone(),
+            kernel_owner: kernel.owner.clone(),
+            kernel_slug: kernel.slug.clone(),
+            kernel_version: version,
+            file_name: file_name.to_string(),
+            submission_description: message.to_string(),
+        };
+
+        info!("Submitting {} v{} to competition {}", kernel, version, competition);
+        debug!("Submitting code to: {}", url);
+
+        let response = self
+            .request(self.http_client().post(&url).json(&request))
+            .await?;
+        Ok(response.json().await?)
+    }
+
+    /// Finds a specific version in a kernel's version history.
+    async fn find_kernel_version(&self, kernel: &KernelRef, version: i32) -> Result<KernelVersion, Error> {
+        let mut page = 1;
+        loop {
+            let versions = self
+                .list_kernel_versions(kernel, page, VERSION_PAGE_SIZE)
+                .await?;
+            let last_page = versions.len() < VERSION_PAGE_SIZE as usize;
+            if let Some(found) = versions.into_iter().find(|v| v.version_number == version) {
+                return Ok(found);
+            }
+            if last_page {
+                return Err(Error::InvalidParameter(format!(
+                    "Kernel {} has no version {}",
+                    kernel, version
+                )));
+            }
+            if page >= MAX_VERSION_PAGES {
+                return Err(Error::InvalidParameter(format!(
+                    "Kernel {} has no version {} among its {} newest versions",
+                    kernel,
+                    version,
+                    page * VERSION_PAGE_SIZE
+                )));
+            }
+            page += 1;
+        }
+    }
+}
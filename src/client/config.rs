@@ -0,0 +1,187 @@
+//! Persistent configuration (`config_view`, `config_set`, `config_unset`).
+
+use super::{kaggle_json_path, read_kaggle_json, update_kaggle_json, KaggleClient};
+use crate::models::{
+    CompetitionRef, ConfigLayer, ConfigLayers, ConfigSource, Error, KaggleConfig, ResolvedConfig,
+};
+use std::path::PathBuf;
+use tracing::{debug, info, warn};
+
+impl KaggleClient {
+    /// Loads configuration values from `~/.kaggle/kaggle.json`.
+    ///
+    /// Keys that are missing from the file are left unset. Values that fail
+    /// validation are ignored with a warning rather than failing startup.
+    ///
+    /// # Returns
+    ///
+    /// Returns the loaded configuration.
+    pub async fn load_config(&self) -> Result<KaggleConfig, Error> {
+        let resolved = self.load_config_layers(ConfigLayers::default()).await?;
+        Ok(resolved.config)
+    }
+
+    /// Loads the config file layer and resolves it together with `layers`.
+    ///
+    /// `layers` usually comes from [`ConfigLayers::discover`] and holds the
+    /// project file, `.env`, environment and command line values. Values that
+    /// fail validation are ignored with a warning rather than failing startup.
+    ///
+    /// # Returns
+    ///
+    /// Returns the effective configuration and where each value came from.
+    pub async fn load_config_layers(&self, mut layers: ConfigLayers) -> Result<ResolvedConfig, Error> {
+        let json = read_kaggle_json().await?;
+        let source = config_file_source();
+        for key in KaggleConfig::KEYS {
+            let value = match json.get(key) {
+                Some(serde_json::Value::String(value)) => Some(value.clone()),
+                Some(serde_json::Value::Bool(value)) => Some(value.to_string()),
+                Some(serde_json::Value::Number(value)) => Some(value.to_string()),
+                _ => None,
+            };
+            layers.replace(source.clone(), key, value);
+        }
+
+        let resolved = layers.resolve();
+        for warning in &resolved.warnings {
+            warn!("{}", warning);
+        }
+        debug!("Loaded configuration: {:?}", resolved.config);
+        self.apply_config(resolved.config.clone()).await?;
+        *self.config_layers.write().await = layers;
+        Ok(resolved)
+    }
+
+    /// Returns the current configuration.
+    pub async fn config(&self) -> KaggleConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Returns the current configuration with the layer each value came from.
+    pub async fn config_sources(&self) -> ResolvedConfig {
+        self.config_layers.read().await.resolve()
+    }
+
+    /// Returns whether `proxy` and `api_base` may be set by a project file,
+    /// a `.env` file or the `config_set` tool, as decided when the
+    /// configuration layers were discovered.
+    pub async fn endpoint_overrides_allowed(&self) -> bool {
+        self.config_layers.read().await.allows_endpoint_overrides()
+    }
+
+    /// Validates, sets and persists a configuration value.
+    ///
+    /// The value is written to the config file layer, so a project file,
+    /// environment variable or command line flag for the same key still
+    /// takes precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - One of `competition`, `path`, `proxy`, `ssl_ca_cert`, `api_base`,
+    ///   `strict_permissions`, `max_attempts`, `requests_per_minute` or `cache`
+    /// * `value` - The new value
+    ///
+    /// # Returns
+    ///
+    /// Returns the effective configuration, or `Error::InvalidParameter` if
+    /// the key is unknown or the value is invalid.
+    pub async fn set_config(&self, key: &str, value: &str) -> Result<KaggleConfig, Error> {
+        let mut validated = KaggleConfig::default();
+        validated.set(key, value)?;
+        self.save_config(key, validated.get(key)).await?;
+        info!("Set configuration value {}", key);
+        Ok(self.config().await)
+    }
+
+    /// Clears and persists a configuration value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - One of `competition`, `path`, `proxy`, `ssl_ca_cert`, `api_base`,
+    ///   `strict_permissions`, `max_attempts`, `requests_per_minute` or `cache`
+    ///
+    /// # Returns
+    ///
+    /// Returns the effective configuration.
+    pub async fn unset_config(&self, key: &str) -> Result<KaggleConfig, Error> {
+        KaggleConfig::default().unset(key)?;
+        self.save_config(key, None).await?;
+        info!("Unset configuration value {}", key);
+        Ok(self.config().await)
+    }
+
+    /// Returns the directory to download files to when none is given: the
+    /// configured `path`, or the current directory.
+    pub async fn download_path(&self, path: Option<&str>) -> PathBuf {
+        match path {
+            Some(path) => PathBuf::from(path),
+            None => self
+                .config
+                .read()
+                .await
+                .path
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(".")),
+        }
+    }
+
+    /// Returns the competition to use for a competition-scoped operation: the
+    /// given one, or the configured default competition.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::InvalidParameter` if no competition is given and no
+    /// default is configured.
+    pub async fn resolve_competition(
+        &self,
+        competition: Option<&CompetitionRef>,
+    ) -> Result<CompetitionRef, Error> {
+        if let Some(competition) = competition {
+            return Ok(competition.clone());
+        }
+        match self.config.read().await.competition {
+            Some(ref slug) => {
+                debug!("Using default competition {}", slug);
+                slug.parse()
+            }
+            None => Err(Error::InvalidParameter(
+                "No competition given and no default competition configured. \
+                 Pass a competition or set one with config_set"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Updates a key in the config file layer, makes the result current and
+    /// writes the key to `kaggle.json`.
+    ///
+    /// The HTTP client is rebuilt first, so a setting it cannot use is not
+    /// saved.
+    async fn save_config(&self, key: &str, value: Option<String>) -> Result<(), Error> {
+        let mut layers = self.config_layers.write().await;
+        let mut updated = layers.clone();
+        updated.replace(config_file_source(), key, value.clone());
+        self.apply_config(updated.resolve().config).await?;
+        *layers = updated;
+
+        update_kaggle_json(|json| match value {
+            Some(value) => {
+                json.insert(key.to_string(), serde_json::Value::String(value));
+            }
+            None => {
+                json.remove(key);
+            }
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+/// Returns the source recorded for values from `kaggle.json`.
+fn config_file_source() -> ConfigSource {
+    ConfigSource {
+        layer: ConfigLayer::ConfigFile,
+        location: kaggle_json_path().map(|path| path.display().to_string()),
+    }
+}
@@ -0,0 +1,231 @@
+//! Encrypted credentials file, an alternative to plaintext `kaggle.json`.
+//!
+//! Once `credentials.enc` exists next to `kaggle.json`, it holds every saved
+//! credential (`username`, `key`, `token` and `profiles`, in the same shape
+//! as `kaggle.json`) and `kaggle.json` keeps only configuration values. The
+//! file is encrypted with AES-256-GCM under a key derived from a passphrase
+//! with scrypt:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "kdf": "scrypt",
+//!   "log_n": 15, "r": 8, "p": 1,
+//!   "salt": "...",
+//!   "nonce": "...",
+//!   "ciphertext": "..."
+//! }
+//! ```
+//!
+//! The passphrase comes from `KAGGLE_CREDENTIALS_PASSPHRASE`, or from
+//! [`set_credentials_passphrase`] (the server binary prompts for it when
+//! started from a terminal).
+
+use super::{kaggle_config_dir, read_kaggle_json, update_kaggle_json, write_private_file};
+use crate::models::{Error, Secret};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::{debug, info};
+use zeroize::Zeroizing;
+
+/// Environment variable holding the passphrase of the encrypted credentials file.
+pub const PASSPHRASE_ENV_VAR: &str = "KAGGLE_CREDENTIALS_PASSPHRASE";
+
+/// Name of the encrypted credentials file in the config directory.
+pub const ENCRYPTED_CREDENTIALS_FILE: &str = "credentials.enc";
+
+/// Keys of `kaggle.json` that hold credentials rather than configuration.
+const CREDENTIAL_KEYS: [&str; 4] = ["username", "key", "token", "profiles"];
+
+/// scrypt cost parameters for newly written files (the scrypt defaults).
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// Passphrase given by [`set_credentials_passphrase`].
+static PASSPHRASE: OnceLock<Secret> = OnceLock::new();
+
+/// On-disk format of the encrypted credentials file.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    kdf: String,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Sets the passphrase used when `KAGGLE_CREDENTIALS_PASSPHRASE` is not set.
+///
+/// Only the first call has an effect.
+pub fn set_credentials_passphrase(passphrase: Secret) {
+    let _ = PASSPHRASE.set(passphrase);
+}
+
+/// Moves the credentials in `kaggle.json` into the encrypted credentials
+/// file, creating it if needed.
+///
+/// # Returns
+///
+/// Returns the path of the encrypted credentials file.
+pub async fn encrypt_credentials() -> Result<PathBuf, Error> {
+    update(|_| {}).await?;
+    encrypted_credentials_path()
+        .ok_or_else(|| Error::Other("Could not determine home directory".to_string()))
+}
+
+/// Returns the path of the encrypted credentials file.
+pub fn encrypted_credentials_path() -> Option<PathBuf> {
+    kaggle_config_dir().map(|dir| dir.join(ENCRYPTED_CREDENTIALS_FILE))
+}
+
+/// Returns whether credentials are kept in the encrypted file.
+pub(crate) fn is_enabled() -> bool {
+    encrypted_credentials_path().is_some_and(|path| path.exists())
+}
+
+/// Returns the passphrase, or an error saying how to provide it.
+fn passphrase() -> Result<Secret, Error> {
+    if let Some(passphrase) = std::env::var(PASSPHRASE_ENV_VAR).ok().filter(|p| !p.is_empty()) {
+        return Ok(passphrase.into());
+    }
+    PASSPHRASE.get().cloned().ok_or_else(|| {
+        Error::AuthenticationError(format!(
+            "{} is encrypted; set {} or start the server from a terminal to enter the passphrase",
+            ENCRYPTED_CREDENTIALS_FILE, PASSPHRASE_ENV_VAR
+        ))
+    })
+}
+
+/// Derives the file key from a passphrase.
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+) -> Result<Zeroizing<[u8; 32]>, Error> {
+    let params = scrypt::Params::new(log_n, r, p, 32)
+        .map_err(|e| Error::Other(format!("Invalid scrypt parameters: {}", e)))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, key.as_mut())
+        .map_err(|e| Error::Other(format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+/// Encrypts `plaintext` into the contents of an encrypted credentials file.
+pub(crate) fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<String, Error> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref()));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| Error::Other("Encrypting credentials failed".to_string()))?;
+
+    let file = EncryptedFile {
+        version: 1,
+        kdf: "scrypt".to_string(),
+        log_n: SCRYPT_LOG_N,
+        r: SCRYPT_R,
+        p: SCRYPT_P,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    Ok(serde_json::to_string_pretty(&file)?)
+}
+
+/// Decrypts the contents of an encrypted credentials file.
+///
+/// A wrong passphrase and a modified file both fail with
+/// `Error::AuthenticationError`.
+pub(crate) fn decrypt(content: &str, passphrase: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let invalid = |what: &str| Error::Other(format!("Invalid {}: {}", ENCRYPTED_CREDENTIALS_FILE, what));
+    let file: EncryptedFile = serde_json::from_str(content).map_err(|e| invalid(&e.to_string()))?;
+    if file.version != 1 || file.kdf != "scrypt" {
+        return Err(invalid(&format!("unsupported version {} / kdf {}", file.version, file.kdf)));
+    }
+    let salt = BASE64.decode(&file.salt).map_err(|_| invalid("bad salt"))?;
+    let nonce = BASE64.decode(&file.nonce).map_err(|_| invalid("bad nonce"))?;
+    let ciphertext = BASE64.decode(&file.ciphertext).map_err(|_| invalid("bad ciphertext"))?;
+    if nonce.len() != 12 {
+        return Err(invalid("bad nonce"));
+    }
+
+    let key = derive_key(passphrase, &salt, file.log_n, file.r, file.p)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref()));
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map(Zeroizing::new)
+        .map_err(|_| {
+            Error::AuthenticationError(format!(
+                "Cannot decrypt {}: wrong passphrase or corrupted file",
+                ENCRYPTED_CREDENTIALS_FILE
+            ))
+        })
+}
+
+/// Reads the encrypted credentials file as a JSON object, or an empty
+/// object if it does not exist.
+pub(crate) async fn read() -> Result<serde_json::Map<String, serde_json::Value>, Error> {
+    let path = encrypted_credentials_path()
+        .ok_or_else(|| Error::Other("Could not determine home directory".to_string()))?;
+    let content = match tokio::fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(serde_json::Map::new()),
+        Err(e) => return Err(e.into()),
+    };
+    debug!("Decrypting {:?}", path);
+    let plaintext = decrypt(&content, passphrase()?.expose())?;
+    match serde_json::from_slice(&plaintext)? {
+        serde_json::Value::Object(map) => Ok(map),
+        _ => Err(Error::Other(format!("Invalid {} format", ENCRYPTED_CREDENTIALS_FILE))),
+    }
+}
+
+/// Applies `update` to the encrypted credentials and writes them back.
+///
+/// If the file does not exist yet, it is created and the credentials in
+/// `kaggle.json` are moved into it first.
+pub(crate) async fn update(
+    update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+) -> Result<(), Error> {
+    let path = encrypted_credentials_path()
+        .ok_or_else(|| Error::Other("Could not determine home directory".to_string()))?;
+    let passphrase = passphrase()?;
+    let migrate = !path.exists();
+    let mut credentials = if migrate {
+        let mut json = read_kaggle_json().await?;
+        CREDENTIAL_KEYS
+            .iter()
+            .filter_map(|key| json.remove(*key).map(|value| (key.to_string(), value)))
+            .collect()
+    } else {
+        read().await?
+    };
+    update(&mut credentials);
+
+    let plaintext = Zeroizing::new(serde_json::to_vec(&credentials)?);
+    write_private_file(&path, &encrypt(&plaintext, passphrase.expose())?).await?;
+
+    if migrate {
+        // Only strip kaggle.json once its credentials are safely encrypted.
+        if read_kaggle_json().await?.keys().any(|key| CREDENTIAL_KEYS.contains(&key.as_str())) {
+            update_kaggle_json(|json| json.retain(|key, _| !CREDENTIAL_KEYS.contains(&key.as_str())))
+                .await?;
+        }
+        info!("Moved credentials from kaggle.json to {:?}", path);
+    }
+    Ok(())
+}
@@ -0,0 +1,188 @@
+//! File upload, download and archive extraction helpers.
+
+use super::KaggleClient;
+use crate::models::{BlobUploadRequest, BlobUploadResponse, Error, UploadDirectory, UploadFile};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info};
+
+impl KaggleClient {
+    /// Uploads a single file and returns the token that references it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Local file to upload
+    /// * `name` - Name of the file on Kaggle
+    /// * `blob_type` - Kind of object the file belongs to (e.g., "model")
+    pub(crate) async fn upload_file(
+        &self,
+        path: &Path,
+        name: &str,
+        blob_type: &str,
+    ) -> Result<UploadFile, Error> {
+        let metadata = tokio::fs::metadata(path).await?;
+        let last_modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_secs() as i64);
+        let request = BlobUploadRequest {
+            type_: blob_type.to_string(),
+            name: name.to_string(),
+            content_length: metadata.len(),
+            last_modified_epoch_seconds: last_modified,
+        };
+
+        let url = self.endpoint("/blobs/upload");
+        debug!("Starting upload of {:?} at: {}", path, url);
+
+        let response = self
+            .request(self.http_client().post(&url).json(&request))
+            .await?;
+        let blob: BlobUploadResponse = response.json().await?;
+
+        // The signed URL carries its own authorization.
+        let file = tokio::fs::File::open(path).await?;
+        let body = reqwest::Body::wrap_stream(tokio_util::io::ReaderStream::new(file));
+        let response = self
+            .http_client()
+            .put(&blob.create_url)
+            .header(reqwest::header::CONTENT_LENGTH, metadata.len())
+            .body(body)
+            .send()
+            .await
+            // Keep the signed URL out of error messages and logs.
+            .map_err(reqwest::Error::without_url)?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(Error::ApiError(crate::models::KaggleError {
+                code: status.to_string(),
+                message: format!("Upload of {} failed: {}", name, text),
+            }));
+        }
+
+        info!("Uploaded {} ({} bytes)", name, metadata.len());
+        Ok(UploadFile { token: blob.token })
+    }
+
+    /// Uploads every file in a directory tree.
+    ///
+    /// Hidden files and directories (names starting with `.`) are skipped, as
+    /// are the names in `exclude` at the top level.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder` - Directory to upload
+    /// * `blob_type` - Kind of object the files belong to (e.g., "model")
+    /// * `exclude` - Top-level file names to skip, such as metadata files
+    ///
+    /// # Returns
+    ///
+    /// Returns the uploaded tree; the root directory's name is empty.
+    pub(crate) async fn upload_directory(
+        &self,
+        folder: &Path,
+        blob_type: &str,
+        exclude: &[&str],
+    ) -> Result<UploadDirectory, Error> {
+        let tree = self.upload_tree(folder.to_path_buf(), String::new(), blob_type, exclude).await?;
+        if tree.files.is_empty() && tree.directories.is_empty() {
+            return Err(Error::InvalidParameter(format!(
+                "No files to upload in {:?}",
+                folder
+            )));
+        }
+        Ok(tree)
+    }
+
+    /// Recursive part of [`Self::upload_directory`].
+    fn upload_tree<'a>(
+        &'a self,
+        folder: PathBuf,
+        name: String,
+        blob_type: &'a str,
+        exclude: &'a [&'a str],
+    ) -> Pin<Box<dyn Future<Output = Result<UploadDirectory, Error>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut entries = Vec::new();
+            let mut reader = tokio::fs::read_dir(&folder).await?;
+            while let Some(entry) = reader.next_entry().await? {
+                entries.push(entry);
+            }
+            entries.sort_by_key(|entry| entry.file_name());
+
+            let mut directory = UploadDirectory {
+                name,
+                ..Default::default()
+            };
+            for entry in entries {
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                if file_name.starts_with('.') || exclude.contains(&file_name.as_str()) {
+                    continue;
+                }
+                if entry.file_type().await?.is_dir() {
+                    let child = self.upload_tree(entry.path(), file_name, blob_type, &[]).await?;
+                    directory.directories.push(child);
+                } else {
+                    let file = self.upload_file(&entry.path(), &file_name, blob_type).await?;
+                    directory.files.push(file);
+                }
+            }
+            Ok(directory)
+        })
+    }
+
+    /// Downloads an authenticated URL to a file, streaming the body to disk.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of bytes written.
+    pub(crate) async fn download_file(&self, url: &str, destination: &Path) -> Result<u64, Error> {
+        debug!("Downloading {} to {:?}", url, destination);
+
+        let mut response = self.request(self.http_client().get(url)).await?;
+        if let Some(parent) = destination.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::File::create(destination).await?;
+        let mut written = 0;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await?;
+
+        info!("Downloaded {} bytes to {:?}", written, destination);
+        Ok(written)
+    }
+}
+
+/// Extracts a `.tar` or `.tar.gz` archive into a directory.
+///
+/// Entries that would be written outside `destination` are skipped.
+pub(crate) async fn extract_archive(archive: &Path, destination: &Path) -> Result<(), Error> {
+    let archive = archive.to_path_buf();
+    let destination = destination.to_path_buf();
+
+    tokio::task::spawn_blocking(move || -> Result<(), Error> {
+        use std::io::{Read, Seek};
+
+        let mut file = std::fs::File::open(&archive)?;
+        let mut magic = [0u8; 2];
+        let is_gzip = file.read(&mut magic)? == 2 && magic == [0x1f, 0x8b];
+        file.rewind()?;
+
+        std::fs::create_dir_all(&destination)?;
+        if is_gzip {
+            tar::Archive::new(flate2::read::GzDecoder::new(file)).unpack(&destination)?;
+        } else {
+            tar::Archive::new(file).unpack(&destination)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| Error::Other(format!("Extraction task failed: {}", e)))?
+}
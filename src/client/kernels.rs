@@ -0,0 +1,409 @@
+//! Kernel (notebook) endpoints.
+
+use super::KaggleClient;
+use crate::models::{
+    Error, KernelBlob, KernelMetadata, KernelPullResponse, KernelPushRequest,
+    KernelPushOptions, KernelPushResponse, KernelRef, KernelSourceFormat, KernelVersion,
+    PulledKernel,
+};
+use crate::notebook::{self, ScriptLanguage};
+use std::path::Path;
+use tracing::{debug, info, warn};
+
+/// Name of the kernel metadata file used by the Kaggle CLI.
+pub(crate) const KERNEL_METADATA_FILE: &str = "kernel-metadata.json";
+
+impl KaggleClient {
+    /// Pulls the source and metadata of a kernel.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel` - The kernel
+    ///
+    /// # Returns
+    ///
+    /// Returns the kernel metadata and source as provided by the API.
+    pub async fn pull_kernel(&self, kernel: &KernelRef) -> Result<KernelPullResponse, Error> {
+        self.pull_kernel_version(kernel, None).await
+    }
+
+    /// Pulls the source and metadata of a specific kernel version.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel` - The kernel
+    /// * `version` - Version number to pull, or `None` for the latest version
+    ///
+    /// # Returns
+    ///
+    /// Returns the kernel metadata and source as provided by the API.
+    pub async fn pull_kernel_version(
+        &self,
+        kernel: &KernelRef,
+        version: Option<i32>,
+    ) -> Result<KernelPullResponse, Error> {
+        let mut url = format!(
+            "{}?userName={}&kernelSlug={}",
+            self.endpoint("/kernels/pull"),
+            urlencoding::encode(&kernel.owner),
+            urlencoding::encode(&kernel.slug)
+        );
+        if let Some(version) = version {
+            url.push_str(&format!("&versionNumber={}", version));
+        }
+
+        debug!("Pulling kernel from: {}", url);
+
+        let response = self.request(self.http_client().get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Lists the versions of a kernel, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel` - The kernel
+    /// * `page` - Page number for pagination
+    /// * `page_size` - Number of versions per page
+    ///
+    /// # Returns
+    ///
+    /// Returns the version number, title, creation time, status and run time
+    /// of each version.
+    pub async fn list_kernel_versions(
+        &self,
+        kernel: &KernelRef,
+        page: i32,
+        page_size: i32,
+    ) -> Result<Vec<KernelVersion>, Error> {
+        let url = format!(
+            "{}?userName={}&kernelSlug={}&page={}&pageSize={}",
+            self.endpoint("/kernels/versions/list"),
+            urlencoding::encode(&kernel.owner),
+            urlencoding::encode(&kernel.slug),
+            page,
+            page_size
+        );
+
+        debug!("Listing kernel versions from: {}", url);
+
+        let response = self.request(self.http_client().get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Produces a unified diff of a kernel's source between two versions.
+    ///
+    /// Notebooks are converted to percent-format scripts first, so the diff
+    /// shows code and markdown changes rather than JSON and output noise.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel` - The kernel
+    /// * `from_version` - The older version number
+    /// * `to_version` - The newer version number
+    ///
+    /// # Returns
+    ///
+    /// Returns the unified diff, which is empty if the sources are identical.
+    pub async fn diff_kernel_versions(
+        &self,
+        kernel: &KernelRef,
+        from_version: i32,
+        to_version: i32,
+    ) -> Result<String, Error> {
+        let (from, to) = tokio::try_join!(
+            self.pull_kernel_version(kernel, Some(from_version)),
+            self.pull_kernel_version(kernel, Some(to_version)),
+        )?;
+
+        Ok(diff_sources(
+            &reviewable_source(&from.blob)?,
+            &reviewable_source(&to.blob)?,
+            &format!("{} v{}", kernel, from_version),
+            &format!("{} v{}", kernel, to_version),
+        ))
+    }
+
+    /// Pushes a new version of a kernel.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The kernel source and settings to push
+    ///
+    /// # Returns
+    ///
+    /// Returns the push response, which includes the new version number or
+    /// an error message if Kaggle rejected the push.
+    pub async fn push_kernel(&self, request: &KernelPushRequest) -> Result<KernelPushResponse, Error> {
+        let url = self.endpoint("/kernels/push");
+
+        debug!("Pushing kernel {} to: {}", request.slug, url);
+
+        let response = self
+            .request(self.http_client().post(&url).json(request))
+            .await?;
+        Ok(response.json().await?)
+    }
+
+    /// Pulls a kernel and writes its source to a directory.
+    ///
+    /// Notebook kernels can be written as percent-format scripts and script
+    /// kernels as notebooks, depending on `format`.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel` - The kernel
+    /// * `path` - Directory to write the files to
+    /// * `format` - Format of the written source file
+    /// * `write_metadata` - Whether to also write `kernel-metadata.json`
+    ///
+    /// # Returns
+    ///
+    /// Returns the paths of the written files.
+    pub async fn pull_kernel_to(
+        &self,
+        kernel: &KernelRef,
+        path: &Path,
+        format: KernelSourceFormat,
+        write_metadata: bool,
+    ) -> Result<PulledKernel, Error> {
+        let pulled = self.pull_kernel(kernel).await?;
+        let (source, extension) = convert_pulled_source(&pulled.blob, format)?;
+
+        let pulled_ref: KernelRef = pulled.metadata.ref_.parse()?;
+        let file_name = format!("{}.{}", pulled_ref.slug, extension);
+
+        tokio::fs::create_dir_all(path).await?;
+        let source_file = path.join(&file_name);
+        info!("Writing kernel source to {:?}", source_file);
+        tokio::fs::write(&source_file, source).await?;
+
+        let metadata_file = if write_metadata {
+            let metadata = pulled.to_metadata(file_name);
+            let metadata_file = path.join(KERNEL_METADATA_FILE);
+            tokio::fs::write(&metadata_file, serde_json::to_string_pretty(&metadata)?).await?;
+            Some(metadata_file)
+        } else {
+            None
+        };
+
+        Ok(PulledKernel {
+            source_file,
+            metadata_file,
+        })
+    }
+
+    /// Pushes a kernel from a directory containing `kernel-metadata.json`.
+    ///
+    /// The source file named by `code_file` is converted to match the
+    /// metadata's `kernel_type`, so a percent-format script can be pushed as
+    /// a notebook kernel and vice versa. Notebooks are then sanitized as
+    /// requested and the result is scanned for credentials; the push is
+    /// refused if any are found unless `options.allow_secrets` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder` - Directory containing `kernel-metadata.json`
+    /// * `options` - Sanitization and secret scanning options
+    ///
+    /// # Returns
+    ///
+    /// Returns the push response, `Error::SecretsDetected` if potential
+    /// secrets were found, or an error if Kaggle rejected the push.
+    pub async fn push_kernel_from(
+        &self,
+        folder: &Path,
+        options: &KernelPushOptions,
+    ) -> Result<KernelPushResponse, Error> {
+        let metadata_path = folder.join(KERNEL_METADATA_FILE);
+        let metadata: KernelMetadata =
+            serde_json::from_str(&tokio::fs::read_to_string(&metadata_path).await.map_err(
+                |e| Error::InvalidParameter(format!("Cannot read {:?}: {}", metadata_path, e)),
+            )?)?;
+        metadata.id.parse::<KernelRef>()?;
+
+        let code_path = folder.join(&metadata.code_file);
+        let code = tokio::fs::read_to_string(&code_path).await.map_err(|e| {
+            Error::InvalidParameter(format!("Cannot read code file {:?}: {}", code_path, e))
+        })?;
+        let text = prepare_push_source(&code, &code_path, &metadata.kernel_type)?;
+        let text = sanitize_push_source(
+            text,
+            &metadata.kernel_type,
+            options,
+            &self.known_secrets().await,
+        )?;
+
+        let request = KernelPushRequest {
+            id: metadata.id_no,
+            slug: metadata.id,
+            new_title: metadata.title,
+            text,
+            language: metadata.language,
+            kernel_type: metadata.kernel_type,
+            is_private: metadata.is_private,
+            enable_gpu: metadata.enable_gpu,
+            enable_tpu: metadata.enable_tpu,
+            enable_internet: metadata.enable_internet,
+            dataset_data_sources: metadata.dataset_sources,
+            competition_data_sources: metadata.competition_sources,
+            kernel_data_sources: metadata.kernel_sources,
+            model_data_sources: metadata.model_sources,
+        };
+
+        let response = self.push_kernel(&request).await?;
+        match response.error {
+            Some(ref error) if !error.is_empty() => {
+                Err(Error::Other(format!("Kernel push failed: {}", error)))
+            }
+            _ => Ok(response),
+        }
+    }
+}
+
+/// Converts pulled kernel source to the requested format.
+///
+/// Returns the converted source together with the file extension to use.
+pub(crate) fn convert_pulled_source(
+    blob: &KernelBlob,
+    format: KernelSourceFormat,
+) -> Result<(String, &'static str), Error> {
+    let is_notebook = blob.kernel_type == "notebook";
+    let language = ScriptLanguage::from_kaggle_language(&blob.language);
+
+    match (format, is_notebook, language) {
+        (KernelSourceFormat::Auto | KernelSourceFormat::Notebook, true, _) => {
+            Ok((blob.source.clone(), "ipynb"))
+        }
+        (KernelSourceFormat::Auto | KernelSourceFormat::Script, false, Some(language)) => {
+            Ok((blob.source.clone(), language.extension()))
+        }
+        (KernelSourceFormat::Auto, false, None) => {
+            let extension = match blob.language.as_str() {
+                "rmarkdown" => "Rmd",
+                "sqlite" => "sql",
+                _ => "txt",
+            };
+            Ok((blob.source.clone(), extension))
+        }
+        (KernelSourceFormat::Notebook, false, Some(language)) => {
+            let notebook = notebook::script_to_notebook(&blob.source, language)?;
+            Ok((notebook::write_notebook(&notebook)?, "ipynb"))
+        }
+        (KernelSourceFormat::Script, true, Some(language)) => {
+            let notebook = notebook::parse_notebook(&blob.source)?;
+            Ok((notebook::notebook_to_script(&notebook), language.extension()))
+        }
+        (_, _, None) => Err(Error::InvalidParameter(format!(
+            "Cannot convert {} kernels between notebook and script formats",
+            blob.language
+        ))),
+    }
+}
+
+/// Sanitizes kernel source about to be pushed and scans it for secrets.
+///
+/// Notebook source is sanitized according to `options`; scripts are only
+/// scanned.
+pub(crate) fn sanitize_push_source(
+    text: String,
+    kernel_type: &str,
+    options: &KernelPushOptions,
+    known_secrets: &[String],
+) -> Result<String, Error> {
+    let (text, findings) = if kernel_type == "notebook" {
+        let mut parsed = notebook::parse_notebook(&text)?;
+        let sanitize = notebook::SanitizeOptions {
+            strip_outputs: options.strip_outputs,
+            remove_widget_state: options.remove_widget_state,
+        };
+        notebook::sanitize_notebook(&mut parsed, &sanitize);
+        let findings = notebook::scan_notebook(&parsed, known_secrets);
+        let text = if options.strip_outputs || options.remove_widget_state {
+            notebook::write_notebook(&parsed)?
+        } else {
+            text
+        };
+        (text, findings)
+    } else {
+        let findings = notebook::scan_text(&text, "script", known_secrets);
+        (text, findings)
+    };
+
+    if findings.is_empty() {
+        return Ok(text);
+    }
+
+    let summary = findings
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ");
+    if options.allow_secrets {
+        warn!("Pushing kernel despite potential secrets: {}", summary);
+        Ok(text)
+    } else {
+        Err(Error::SecretsDetected(format!(
+            "{}. Remove them or set allow_secrets to push anyway",
+            summary
+        )))
+    }
+}
+
+/// Returns kernel source in a form suitable for line-based diffs.
+///
+/// Notebooks in supported languages are converted to scripts; everything
+/// else is returned unchanged.
+pub(crate) fn reviewable_source(blob: &KernelBlob) -> Result<String, Error> {
+    let format = if ScriptLanguage::from_kaggle_language(&blob.language).is_some() {
+        KernelSourceFormat::Script
+    } else {
+        KernelSourceFormat::Auto
+    };
+    convert_pulled_source(blob, format).map(|(source, _)| source)
+}
+
+/// Produces a unified diff between two sources with three lines of context.
+pub(crate) fn diff_sources(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_name, new_name)
+        .to_string()
+}
+
+/// Converts a local source file to the form expected for `kernel_type`.
+pub(crate) fn prepare_push_source(
+    code: &str,
+    code_path: &Path,
+    kernel_type: &str,
+) -> Result<String, Error> {
+    let extension = code_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+    let is_notebook_file = extension == "ipynb";
+
+    match (is_notebook_file, kernel_type == "notebook") {
+        (true, true) => {
+            // Validate before uploading so a broken file fails locally.
+            notebook::parse_notebook(code)?;
+            Ok(code.to_string())
+        }
+        (false, true) => {
+            let language = ScriptLanguage::from_extension(extension).ok_or_else(|| {
+                Error::InvalidParameter(format!(
+                    "Cannot convert {:?} to a notebook (expected .py or .R)",
+                    code_path
+                ))
+            })?;
+            let notebook = notebook::script_to_notebook(code, language)?;
+            notebook::write_notebook(&notebook)
+        }
+        (true, false) => {
+            let notebook = notebook::parse_notebook(code)?;
+            Ok(notebook::notebook_to_script(&notebook))
+        }
+        (false, false) => Ok(code.to_string()),
+    }
+}
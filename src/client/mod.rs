@@ -3,16 +3,42 @@
 //! This module provides a client for interacting with the Kaggle API, including
 //! authentication, credential management, and HTTP request handling.
 
-use crate::models::{Error, KaggleCredentials, KaggleConfig};
+use crate::models::secret::redact_url;
+use crate::models::{
+    AccessToken, AuthenticateOptions, ConfigLayers, CredentialSource, Error, KaggleAuth,
+    KaggleCredentials, KaggleConfig,
+};
 use reqwest::{Client, RequestBuilder};
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use cache::{Lookup, ResponseCache};
+use rate_limit::RateLimiter;
 use tracing::{debug, info, warn, error};
 
+mod cache;
+mod competitions;
+mod config;
+mod credential_store;
+mod files;
+mod kernels;
+mod models;
+mod profiles;
+mod rate_limit;
+mod retry;
+mod session;
+
+pub use credential_store::{
+    encrypt_credentials, encrypted_credentials_path, set_credentials_passphrase,
+    ENCRYPTED_CREDENTIALS_FILE, PASSPHRASE_ENV_VAR,
+};
+pub use profiles::DEFAULT_PROFILE;
+pub use rate_limit::DEFAULT_REQUESTS_PER_MINUTE;
+pub use retry::RetryPolicy;
+
 #[cfg(test)]
 mod tests;
 
-/// Base URL for the Kaggle API
+/// Default base URL for the Kaggle API
 const KAGGLE_API_BASE: &str = "https://www.kaggle.com/api/v1";
 
 /// Kaggle API client that handles authentication and HTTP requests.
@@ -40,32 +66,56 @@ const KAGGLE_API_BASE: &str = "https://www.kaggle.com/api/v1";
 /// }
 /// ```
 pub struct KaggleClient {
-    http_client: Client,
-    credentials: Arc<RwLock<Option<KaggleCredentials>>>,
-    #[allow(dead_code)]
+    http_client: Arc<std::sync::RwLock<Client>>,
+    credentials: Arc<RwLock<Option<KaggleAuth>>>,
+    session: Arc<RwLock<Session>>,
     config: Arc<RwLock<KaggleConfig>>,
-    #[cfg(test)]
+    config_layers: Arc<RwLock<ConfigLayers>>,
+    api_base: Arc<std::sync::RwLock<String>>,
     api_base_override: Option<String>,
+    retry_policy_override: Option<RetryPolicy>,
+    rate_limiter: Arc<RateLimiter>,
+    cache: Arc<ResponseCache>,
     #[cfg(test)]
     skip_save_credentials: bool,
 }
 
+/// How the active credentials were obtained.
+#[derive(Debug, Clone, Default)]
+struct Session {
+    /// Profile the credentials belong to, if any
+    profile: Option<String>,
+    /// Where the credentials came from
+    source: Option<CredentialSource>,
+    /// Whether the Kaggle API has accepted the credentials
+    verified: bool,
+}
+
+impl Default for KaggleClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl KaggleClient {
     /// Creates a new Kaggle API client instance.
     /// 
     /// Initializes the HTTP client with appropriate user agent and default settings.
     pub fn new() -> Self {
-        let http_client = Client::builder()
-            .user_agent("kaggle-mcp-rs/0.1.0")
-            .build()
-            .expect("Failed to create HTTP client");
+        let http_client =
+            build_http_client(&KaggleConfig::default()).expect("Failed to create HTTP client");
 
         Self {
-            http_client,
+            http_client: Arc::new(std::sync::RwLock::new(http_client)),
             credentials: Arc::new(RwLock::new(None)),
+            session: Arc::new(RwLock::new(Session::default())),
             config: Arc::new(RwLock::new(KaggleConfig::default())),
-            #[cfg(test)]
+            config_layers: Arc::new(RwLock::new(ConfigLayers::default())),
+            api_base: Arc::new(std::sync::RwLock::new(KAGGLE_API_BASE.to_string())),
             api_base_override: None,
+            retry_policy_override: None,
+            rate_limiter: Arc::new(RateLimiter::new(DEFAULT_REQUESTS_PER_MINUTE)),
+            cache: Arc::new(ResponseCache::default()),
             #[cfg(test)]
             skip_save_credentials: false,
         }
@@ -74,7 +124,8 @@ impl KaggleClient {
     /// Authenticates with the Kaggle API using the provided credentials.
     /// 
     /// This method tests the credentials by making a simple API call to the competitions
-    /// endpoint. If successful, the credentials are stored and optionally saved to disk.
+    /// endpoint. If successful, the credentials are stored and saved to disk, unless
+    /// `kaggle.json` already holds credentials for a different username.
     /// 
     /// # Arguments
     /// 
@@ -86,43 +137,86 @@ impl KaggleClient {
     /// Returns `Ok(())` if authentication is successful, or an error if the credentials
     /// are invalid or if there's a network issue.
     pub async fn authenticate(&self, username: String, key: String) -> Result<(), Error> {
-        info!("Authenticating with Kaggle API");
-        debug!("Username: {}", username);
+        let options = AuthenticateOptions {
+            persist: true,
+            ..Default::default()
+        };
+        self.authenticate_with(username, key, &options).await
+    }
+
+    /// Authenticates like [`Self::authenticate`], with control over whether
+    /// and where the credentials are saved.
+    /// 
+    /// Saving to a profile that holds credentials for a different username
+    /// fails with `Error::InvalidParameter` unless `options.force` is set.
+    /// This is checked before anything is changed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `username` - The Kaggle username
+    /// * `key` - The Kaggle API key
+    /// * `options` - Profile to save to, and whether to save at all
+    pub async fn authenticate_with(
+        &self,
+        username: String,
+        key: String,
+        options: &AuthenticateOptions,
+    ) -> Result<(), Error> {
+        let profile = options.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+        profiles::validate_profile_name(profile)?;
+        if options.persist && !options.force {
+            profiles::check_overwrite(profile, &username).await?;
+        }
+
+        let credentials: KaggleAuth = KaggleCredentials { username, key: key.into() }.into();
+        self.verify_credentials(&credentials).await?;
+
+        let session = Session {
+            profile: options.persist.then(|| profile.to_string()),
+            source: Some(CredentialSource::ToolCall),
+            verified: true,
+        };
+        self.set_credentials(credentials.clone(), session).await;
+        if options.persist {
+            if let KaggleAuth::Basic(ref credentials) = credentials {
+                let (username, key) = (&credentials.username, credentials.key.expose());
+                #[cfg(test)]
+                if !self.skip_save_credentials {
+                    self.save_credentials(profile, username, key, options.encrypt).await?;
+                }
+                #[cfg(not(test))]
+                self.save_credentials(profile, username, key, options.encrypt).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks credentials against the Kaggle API without storing them.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `credentials` - The credentials to check
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if the API accepts the credentials, or
+    /// `Error::AuthenticationError` if it rejects them.
+    pub async fn verify_credentials(&self, credentials: &KaggleAuth) -> Result<(), Error> {
+        info!("Authenticating with Kaggle API using {} auth", credentials.scheme());
         
         // Test authentication by making a simple API call
-        #[cfg(test)]
-        let test_url = {
-            if let Some(ref base) = self.api_base_override {
-                // For tests, use the override URL directly
-                format!("{}/api/v1/competitions/list", base)
-            } else {
-                format!("{}/competitions/list", KAGGLE_API_BASE)
-            }
-        };
-        #[cfg(not(test))]
-        let test_url = format!("{}/competitions/list", KAGGLE_API_BASE);
+        let test_url = self.endpoint("/competitions/list");
         
         debug!("Testing authentication with URL: {}", test_url);
         
-        let response = self.http_client
-            .get(&test_url)
-            .basic_auth(&username, Some(&key))
+        self.rate_limiter.acquire().await;
+        let response = credentials
+            .apply(self.http_client().get(&test_url))
             .send()
             .await?;
 
         if response.status().is_success() {
             info!("Authentication successful");
-            let mut creds = self.credentials.write().await;
-            *creds = Some(KaggleCredentials { username: username.clone(), key: key.clone() });
-            
-            // Save credentials to file
-            #[cfg(test)]
-            if !self.skip_save_credentials {
-                self.save_credentials(&username, &key).await?;
-            }
-            #[cfg(not(test))]
-            self.save_credentials(&username, &key).await?;
-            
             Ok(())
         } else {
             let status = response.status();
@@ -147,42 +241,42 @@ impl KaggleClient {
     /// Saves credentials to the kaggle.json file in the user's home directory.
     /// 
     /// The credentials are saved to `~/.kaggle/kaggle.json` with restricted
-    /// permissions (0o600 on Unix systems).
+    /// permissions (0o600 on Unix systems), or to the encrypted credentials
+    /// file if it exists or `encrypt` is set. Other keys in the file, such as
+    /// configuration values and other profiles, are preserved.
     /// 
     /// # Arguments
     /// 
+    /// * `profile` - Profile to save to; `default` is the top level
     /// * `username` - The Kaggle username
     /// * `key` - The Kaggle API key
-    async fn save_credentials(&self, username: &str, key: &str) -> Result<(), Error> {
-        info!("Saving credentials to kaggle.json");
-        
-        let kaggle_dir = directories::UserDirs::new()
-            .ok_or_else(|| Error::Other("Could not determine home directory".to_string()))?
-            .home_dir()
-            .join(".kaggle");
-
-        debug!("Creating directory: {:?}", kaggle_dir);
-        tokio::fs::create_dir_all(&kaggle_dir).await?;
-
-        let kaggle_json = serde_json::json!({
-            "username": username,
-            "key": key
-        });
-
-        let kaggle_json_path = kaggle_dir.join("kaggle.json");
-        debug!("Writing credentials to: {:?}", kaggle_json_path);
-        tokio::fs::write(&kaggle_json_path, serde_json::to_string_pretty(&kaggle_json)?).await?;
-
-        // Set file permissions to 0o600 on Unix
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let metadata = tokio::fs::metadata(&kaggle_json_path).await?;
-            let mut permissions = metadata.permissions();
-            permissions.set_mode(0o600);
-            tokio::fs::set_permissions(&kaggle_json_path, permissions).await?;
-            debug!("Set file permissions to 0o600");
-        }
+    /// * `encrypt` - Start using the encrypted credentials file
+    async fn save_credentials(
+        &self,
+        profile: &str,
+        username: &str,
+        key: &str,
+        encrypt: bool,
+    ) -> Result<(), Error> {
+        info!("Saving credentials");
+
+        let credentials = serde_json::json!({ "username": username, "key": key });
+        // Keep configuration values stored in the same file.
+        update_credentials_json(encrypt, |json| {
+            if profile == DEFAULT_PROFILE {
+                json.insert("username".to_string(), username.into());
+                json.insert("key".to_string(), key.into());
+                return;
+            }
+            let profiles = json
+                .entry("profiles")
+                .or_insert_with(|| serde_json::Value::Object(Default::default()));
+            if !profiles.is_object() {
+                *profiles = serde_json::Value::Object(Default::default());
+            }
+            profiles[profile] = credentials;
+        })
+        .await?;
 
         info!("Credentials saved successfully");
         Ok(())
@@ -190,8 +284,16 @@ impl KaggleClient {
 
     /// Loads Kaggle credentials from environment variables or file.
     /// 
-    /// This method first checks for `KAGGLE_USERNAME` and `KAGGLE_KEY` environment
-    /// variables. If not found, it looks for credentials in `~/.kaggle/kaggle.json`.
+    /// The first credential found is used, in this order:
+    /// 
+    /// 1. An access token in `KAGGLE_API_TOKEN`
+    /// 2. `KAGGLE_USERNAME` and `KAGGLE_KEY`
+    /// 3. The profile named by `KAGGLE_PROFILE` in `~/.kaggle/kaggle.json`
+    /// 4. An access token in `~/.kaggle/access_token`
+    /// 5. The default credentials in `~/.kaggle/kaggle.json`
+    /// 
+    /// Access tokens are sent as bearer tokens, username/key pairs with basic
+    /// auth.
     /// 
     /// # Returns
     /// 
@@ -201,56 +303,134 @@ impl KaggleClient {
         info!("Loading Kaggle credentials");
         
         // First, check environment variables
+        if let Some(token) = std::env::var("KAGGLE_API_TOKEN").ok().filter(|t| !t.trim().is_empty()) {
+            info!("Found access token in environment variables");
+            let username = std::env::var("KAGGLE_USERNAME").ok();
+            let token = AccessToken { token: token.trim().into(), username };
+            self.set_credentials(token.into(), Session::from_source(CredentialSource::Environment, None))
+                .await;
+            return Ok(());
+        }
         if let (Ok(username), Ok(key)) = (
             std::env::var("KAGGLE_USERNAME"),
             std::env::var("KAGGLE_KEY"),
         ) {
             info!("Found credentials in environment variables");
-            let mut credentials = self.credentials.write().await;
-            *credentials = Some(KaggleCredentials { username, key });
+            let credentials = KaggleCredentials { username, key: key.into() };
+            self.set_credentials(credentials.into(), Session::from_source(CredentialSource::Environment, None))
+                .await;
             return Ok(());
         }
         
-        // Then, check kaggle.json file
-        let kaggle_json_path = match directories::UserDirs::new() {
-            Some(dirs) => dirs.home_dir().join(".kaggle").join("kaggle.json"),
+        // Then, check the credential files
+        let kaggle_json_path = match kaggle_json_path() {
+            Some(path) => path,
             None => {
                 warn!("Could not determine home directory");
                 return Err(Error::NotAuthenticated);
             }
         };
+        let strict = self.config.read().await.strict_permissions.unwrap_or(false);
+        if let Some(path) = access_token_path() {
+            check_credential_file(&path, strict)?;
+        }
+        check_credential_file(&kaggle_json_path, strict)?;
+        if let Some(path) = encrypted_credentials_path() {
+            check_credential_file(&path, strict)?;
+        }
+
+        if let Ok(profile) = std::env::var("KAGGLE_PROFILE") {
+            debug!("Loading profile {} from {:?}", profile, kaggle_json_path);
+            let credentials = profiles::read_profiles().await?.remove(&profile);
+            return match credentials {
+                Some(credentials) => {
+                    let session = Session::from_source(CredentialSource::Profile, Some(profile));
+                    self.set_credentials(credentials, session).await;
+                    Ok(())
+                }
+                None => {
+                    error!("Profile {} not found in kaggle.json", profile);
+                    Err(Error::NotAuthenticated)
+                }
+            };
+        }
+
+        if let Some(token) = read_access_token().await? {
+            info!("Found access token file");
+            let session = Session::from_source(CredentialSource::AccessTokenFile, None);
+            self.set_credentials(token.into(), session).await;
+            return Ok(());
+        }
 
         debug!("Checking for kaggle.json at: {:?}", kaggle_json_path);
 
-        if kaggle_json_path.exists() {
-            info!("Found kaggle.json file");
-            let content = tokio::fs::read_to_string(&kaggle_json_path).await?;
-            let creds: serde_json::Value = serde_json::from_str(&content)?;
-            
-            if let (Some(username), Some(key)) = (
-                creds.get("username").and_then(|v| v.as_str()),
-                creds.get("key").and_then(|v| v.as_str()),
-            ) {
-                let mut credentials = self.credentials.write().await;
-                *credentials = Some(KaggleCredentials {
-                    username: username.to_string(),
-                    key: key.to_string(),
-                });
-                Ok(())
-            } else {
-                error!("Invalid kaggle.json format");
-                Err(Error::Other("Invalid kaggle.json format".to_string()))
+        let encrypted = credential_store::is_enabled();
+        if kaggle_json_path.exists() || encrypted {
+            info!("Found {}", if encrypted { ENCRYPTED_CREDENTIALS_FILE } else { "kaggle.json file" });
+            match profiles::read_profiles().await?.remove(DEFAULT_PROFILE) {
+                Some(credentials) => {
+                    let source = if encrypted {
+                        CredentialSource::EncryptedFile
+                    } else {
+                        CredentialSource::ConfigFile
+                    };
+                    let session = Session::from_source(source, Some(DEFAULT_PROFILE.to_string()));
+                    self.set_credentials(credentials, session).await;
+                    Ok(())
+                }
+                None => {
+                    error!("Invalid kaggle.json format");
+                    Err(Error::Other("Invalid kaggle.json format".to_string()))
+                }
             }
         } else {
-            warn!("No credentials found in environment variables or kaggle.json");
+            warn!("No credentials found in environment variables or credential files");
             Err(Error::NotAuthenticated)
         }
     }
 
+    /// Makes `credentials` the ones requests are made with.
+    async fn set_credentials(&self, credentials: KaggleAuth, session: Session) {
+        *self.credentials.write().await = Some(credentials);
+        *self.session.write().await = session;
+    }
+
+    /// Returns the Kaggle API keys and tokens known to this process.
+    /// 
+    /// This includes the active credential, `KAGGLE_KEY`, `KAGGLE_API_TOKEN`,
+    /// the access token file and every profile in `~/.kaggle/kaggle.json`, so
+    /// they can be detected in content about to be published.
+    pub(crate) async fn known_secrets(&self) -> Vec<String> {
+        let mut secrets = Vec::new();
+
+        if let Some(creds) = self.credentials.read().await.as_ref() {
+            secrets.push(creds.secret().to_string());
+        }
+        for name in ["KAGGLE_KEY", "KAGGLE_API_TOKEN"] {
+            if let Ok(secret) = std::env::var(name) {
+                secrets.push(secret.trim().to_string());
+            }
+        }
+        if let Ok(Some(token)) = read_access_token().await {
+            secrets.push(token.token.expose().to_string());
+        }
+        if let Ok(profiles) = profiles::read_profiles().await {
+            secrets.extend(profiles.into_values().map(|creds| creds.secret().to_string()));
+        }
+
+        secrets.retain(|secret| !secret.is_empty());
+        secrets.sort();
+        secrets.dedup();
+        secrets
+    }
+
     /// Makes an authenticated HTTP request to the Kaggle API.
     /// 
     /// This method adds authentication headers to the request and handles
-    /// common error cases.
+    /// common error cases. Responses of read-only endpoints are served from
+    /// the response cache while fresh. Other requests wait for the
+    /// client-side rate limit, and transient failures are retried according
+    /// to the [retry policy](Self::retry_policy).
     /// 
     /// # Arguments
     /// 
@@ -261,16 +441,54 @@ impl KaggleClient {
     /// Returns the HTTP response if successful, or an error if authentication
     /// fails or there's a network issue.
     pub(crate) async fn request(&self, builder: RequestBuilder) -> Result<reqwest::Response, Error> {
-        let creds = self.credentials.read().await;
-        let creds = creds.as_ref().ok_or(Error::NotAuthenticated)?;
+        let (client, request, credentials) = {
+            let creds = self.credentials.read().await;
+            let creds = creds.as_ref().ok_or(Error::NotAuthenticated)?;
+            let (client, request) = creds.apply(builder).build_split();
+            (client, request, creds.clone())
+        };
+        let mut request = request?;
+        let api_base = self.api_base();
+
+        let cache_key = self.cache.key(&request, &api_base, &credentials).await;
+        let mut unconditional = None;
+        if let Some(ref key) = cache_key {
+            let plain = request.try_clone();
+            match self.cache.lookup(key, &mut request).await {
+                Lookup::Fresh(response) => return Ok(response),
+                Lookup::Stale => unconditional = plain,
+                Lookup::Miss => {}
+            }
+        }
+        let is_get = request.method() == reqwest::Method::GET;
+        let url = request.url().to_string();
         
-        let response = builder
-            .basic_auth(&creds.username, Some(&creds.key))
-            .send()
-            .await?;
+        let mut response = self.send_with_retries(&client, request).await?;
         
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some(ref key) = cache_key {
+                if let Some(response) = self.cache.revalidated(key).await {
+                    return Ok(response);
+                }
+            }
+            // The entry was dropped while revalidating it; ask for the full
+            // response instead.
+            if let Some(request) = unconditional {
+                debug!("Cached response for {} is gone; fetching it again", url);
+                response = self.send_with_retries(&client, request).await?;
+            }
+        }
         if response.status().is_success() {
-            Ok(response)
+            if !self.session.read().await.verified {
+                self.session.write().await.verified = true;
+            }
+            if !is_get {
+                self.cache.invalidate(&url, &api_base, &credentials).await;
+            }
+            match cache_key {
+                Some(ref key) => self.cache.store(key, response).await,
+                None => Ok(response),
+            }
         } else {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
@@ -281,23 +499,68 @@ impl KaggleClient {
         }
     }
 
-    /// Returns a reference to the underlying HTTP client.
+    /// Returns the underlying HTTP client.
     /// 
     /// This can be used to make custom requests while reusing the client's
-    /// connection pool.
-    pub fn http_client(&self) -> &Client {
-        &self.http_client
+    /// connection pool, proxy and TLS settings.
+    pub fn http_client(&self) -> Client {
+        self.http_client
+            .read()
+            .expect("HTTP client lock poisoned")
+            .clone()
+    }
+
+    /// Makes `config` current, rebuilding the HTTP client so its proxy and
+    /// CA certificate settings take effect.
+    pub(crate) async fn apply_config(&self, config: KaggleConfig) -> Result<(), Error> {
+        let http_client = build_http_client(&config)?;
+        *self.http_client.write().expect("HTTP client lock poisoned") = http_client;
+        if let Some(ref api_base) = config.api_base {
+            let host = reqwest::Url::parse(api_base).ok().and_then(|url| url.host_str().map(str::to_string));
+            if !host.is_some_and(|host| host == "kaggle.com" || host.ends_with(".kaggle.com")) {
+                warn!(
+                    "api_base is {}, not kaggle.com; Kaggle credentials will be sent there",
+                    redact_url(api_base)
+                );
+            }
+        }
+        *self.api_base.write().expect("API base lock poisoned") = config
+            .api_base
+            .clone()
+            .unwrap_or_else(|| KAGGLE_API_BASE.to_string());
+        self.rate_limiter
+            .set_rate(config.requests_per_minute.unwrap_or(DEFAULT_REQUESTS_PER_MINUTE))
+            .await;
+        self.cache
+            .configure(
+                config.cache.unwrap_or_default(),
+                kaggle_config_dir().map(|dir| dir.join("cache")),
+            )
+            .await;
+        *self.config.write().await = config;
+        Ok(())
     }
 
     /// Returns the base URL for the Kaggle API.
-    pub fn api_base() -> &'static str {
-        KAGGLE_API_BASE
+    ///
+    /// This is the base given to [`Self::with_api_base`], else the configured
+    /// `api_base`, else `https://www.kaggle.com/api/v1`.
+    pub fn api_base(&self) -> String {
+        match self.api_base_override {
+            Some(ref base) => base.clone(),
+            None => self.api_base.read().expect("API base lock poisoned").clone(),
+        }
     }
 
-    /// Test-only method to override the API base URL.
-    #[cfg(test)]
-    pub fn with_api_base(mut self, base: String) -> Self {
-        self.api_base_override = Some(base);
+    /// Builds the full URL for an API endpoint path such as `/competitions/list`.
+    pub(crate) fn endpoint(&self, path: &str) -> String {
+        format!("{}{}", self.api_base(), path)
+    }
+
+    /// Sends every request to `base` (e.g., `http://localhost:8080/api/v1`)
+    /// instead of the Kaggle API, regardless of the configured `api_base`.
+    pub fn with_api_base(mut self, base: impl Into<String>) -> Self {
+        self.api_base_override = Some(base.into().trim_end_matches('/').to_string());
         self
     }
 
@@ -329,7 +592,7 @@ impl KaggleClient {
         sort_by: String,
         page: i32,
     ) -> Result<Vec<crate::models::Competition>, Error> {
-        let mut url = format!("{}/competitions/list", Self::api_base());
+        let mut url = self.endpoint("/competitions/list");
         
         // Build query parameters
         let mut query_params = vec![];
@@ -353,18 +616,224 @@ impl KaggleClient {
             url = format!("{}?{}", url, query_params.join("&"));
         }
         
-        #[cfg(test)]
-        let url = if let Some(ref base) = self.api_base_override {
-            url.replace(Self::api_base(), base)
-        } else {
-            url
-        };
-        
         debug!("Fetching competitions from: {}", url);
         
-        let response = self.request(self.http_client.get(&url)).await?;
+        let response = self.request(self.http_client().get(&url)).await?;
         let competitions: Vec<crate::models::Competition> = response.json().await?;
         
         Ok(competitions)
     }
-}
\ No newline at end of file
+}
+
+impl Session {
+    /// Creates an unverified session for credentials loaded from `source`.
+    fn from_source(source: CredentialSource, profile: Option<String>) -> Self {
+        Self {
+            profile,
+            source: Some(source),
+            verified: false,
+        }
+    }
+}
+
+/// Builds the HTTP client for a configuration.
+/// 
+/// If `config.proxy` is set, all requests go through it except for hosts
+/// listed in `NO_PROXY`. Otherwise the `HTTPS_PROXY`, `HTTP_PROXY`,
+/// `ALL_PROXY` and `NO_PROXY` environment variables are honored. If
+/// `config.ssl_ca_cert` is set, the certificates in that PEM file are trusted
+/// in addition to the system roots, as needed behind TLS-inspecting proxies.
+pub(crate) fn build_http_client(config: &KaggleConfig) -> Result<Client, Error> {
+    let mut builder = Client::builder().user_agent("kaggle-mcp-rs/0.1.0");
+
+    if let Some(ref proxy) = config.proxy {
+        debug!("Using proxy {}", redact_url(proxy));
+        let proxy = reqwest::Proxy::all(proxy).map_err(|e| {
+            Error::InvalidParameter(format!("Invalid proxy '{}': {}", redact_url(proxy), e))
+        })?
+            .no_proxy(reqwest::NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(ref path) = config.ssl_ca_cert {
+        debug!("Trusting CA certificates from {:?}", path);
+        for certificate in crate::models::load_ca_certificates(path)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Returns the path of `kaggle.json` in the [config directory](kaggle_config_dir),
+/// which holds the credentials and configuration values.
+pub(crate) fn kaggle_json_path() -> Option<std::path::PathBuf> {
+    kaggle_config_dir().map(|dir| dir.join("kaggle.json"))
+}
+
+/// Returns the directory holding the credential files: `KAGGLE_CONFIG_DIR`
+/// if set, as with the official Kaggle CLI, else `~/.kaggle`.
+pub(crate) fn kaggle_config_dir() -> Option<std::path::PathBuf> {
+    match std::env::var_os("KAGGLE_CONFIG_DIR").filter(|dir| !dir.is_empty()) {
+        Some(dir) => Some(dir.into()),
+        None => directories::UserDirs::new().map(|dirs| dirs.home_dir().join(".kaggle")),
+    }
+}
+
+/// Checks that a credential file is private to the current user.
+///
+/// A file that other users can read or write, or that belongs to another
+/// user, is reported with a warning, or refused with
+/// `Error::InsecureCredentials` when `strict` is set. Missing files pass.
+pub(crate) fn check_credential_file(path: &std::path::Path, strict: bool) -> Result<(), Error> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = match std::fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut problems = Vec::new();
+        let mode = metadata.mode() & 0o777;
+        if mode & 0o077 != 0 {
+            problems.push(format!(
+                "is accessible by other users (mode {:o}); run `chmod 600 {}`",
+                mode,
+                path.display()
+            ));
+        }
+        if metadata.uid() != rustix::process::geteuid().as_raw() {
+            problems.push("is owned by another user".to_string());
+        }
+        if problems.is_empty() {
+            return Ok(());
+        }
+
+        let message = format!("{} {}", path.display(), problems.join(" and "));
+        if strict {
+            error!("Refusing to read credentials: {}", message);
+            return Err(Error::InsecureCredentials(message));
+        }
+        warn!("{}", message);
+    }
+    #[cfg(not(unix))]
+    let _ = (path, strict);
+    Ok(())
+}
+
+/// Returns the path of `~/.kaggle/access_token` (or `access_token` in
+/// `KAGGLE_CONFIG_DIR`), which holds an API access token.
+pub(crate) fn access_token_path() -> Option<std::path::PathBuf> {
+    kaggle_json_path().map(|path| path.with_file_name("access_token"))
+}
+
+/// Reads the access token file, or returns `None` if it does not exist or is
+/// empty.
+pub(crate) async fn read_access_token() -> Result<Option<AccessToken>, Error> {
+    let Some(path) = access_token_path() else {
+        return Ok(None);
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(content) if !content.trim().is_empty() => Ok(Some(AccessToken {
+            token: content.trim().into(),
+            username: None,
+        })),
+        Ok(_) => Ok(None),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Reads `kaggle.json` as a JSON object, or an empty object if it does not
+/// exist.
+pub(crate) async fn read_kaggle_json() -> Result<serde_json::Map<String, serde_json::Value>, Error> {
+    let path = kaggle_json_path()
+        .ok_or_else(|| Error::Other("Could not determine home directory".to_string()))?;
+    match tokio::fs::read_to_string(&path).await {
+        Ok(content) => match serde_json::from_str(&content)? {
+            serde_json::Value::Object(map) => Ok(map),
+            _ => Err(Error::Other("Invalid kaggle.json format".to_string())),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(serde_json::Map::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Applies `update` to the contents of `kaggle.json` and writes it back with
+/// restricted permissions (0o600 on Unix systems).
+pub(crate) async fn update_kaggle_json(
+    update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+) -> Result<(), Error> {
+    let path = kaggle_json_path()
+        .ok_or_else(|| Error::Other("Could not determine home directory".to_string()))?;
+    let mut json = read_kaggle_json().await?;
+    update(&mut json);
+    write_private_file(&path, &serde_json::to_string_pretty(&json)?).await
+}
+
+/// Reads the saved credentials (`username`, `key`, `token` and `profiles`)
+/// from the encrypted credentials file if it exists, else from `kaggle.json`.
+pub(crate) async fn read_credentials_json() -> Result<serde_json::Map<String, serde_json::Value>, Error> {
+    if credential_store::is_enabled() {
+        credential_store::read().await
+    } else {
+        read_kaggle_json().await
+    }
+}
+
+/// Applies `update` to the saved credentials, in the encrypted credentials
+/// file if it exists or `encrypt` is set, else in `kaggle.json`.
+pub(crate) async fn update_credentials_json(
+    encrypt: bool,
+    update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+) -> Result<(), Error> {
+    if encrypt || credential_store::is_enabled() {
+        credential_store::update(update).await
+    } else {
+        update_kaggle_json(update).await
+    }
+}
+
+/// Writes a file that only the current user may read (0o600 on Unix
+/// systems), creating its directory if needed.
+///
+/// The contents go to a temporary file that is created with those
+/// permissions and then renamed over `path`, so the file is never readable
+/// by others, even briefly, and is never left half written.
+pub(crate) async fn write_private_file(path: &std::path::Path, contents: &str) -> Result<(), Error> {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use tokio::io::AsyncWriteExt;
+    static TEMP_FILES: AtomicU64 = AtomicU64::new(0);
+
+    if let Some(dir) = path.parent() {
+        debug!("Creating directory: {:?}", dir);
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let temp_path = path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        file_name,
+        std::process::id(),
+        TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    debug!("Writing {:?}", path);
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let result = async {
+        let mut file = options.open(&temp_path).await?;
+        file.write_all(contents.as_bytes()).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(&temp_path, path).await
+    }
+    .await;
+    if let Err(e) = result {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
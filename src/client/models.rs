@@ -0,0 +1,497 @@
+//! Model endpoints.
+
+use super::files::extract_archive;
+use super::KaggleClient;
+use crate::models::{
+    Error, Model, ModelInstance, ModelInstanceMetadata, ModelInstanceRef,
+    ModelInstanceUpdateRequest, ModelInstanceVersionListResponse, ModelInstanceVersionRequest,
+    ModelListResponse, ModelRef, ModelMetadata, ModelResponse,
+    ModelUpdateRequest,
+};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Name of the model metadata file used by the Kaggle CLI.
+pub(crate) const MODEL_METADATA_FILE: &str = "model-metadata.json";
+
+/// Name of the model instance metadata file used by the Kaggle CLI.
+pub(crate) const MODEL_INSTANCE_METADATA_FILE: &str = "model-instance-metadata.json";
+
+impl KaggleClient {
+    /// Lists models from the Kaggle API.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - Search terms to filter models
+    /// * `owner` - Only list models owned by this user or organization
+    /// * `sort_by` - Sort order (e.g., "hotness", "downloadCount", "voteCount")
+    /// * `page_size` - Number of models per page
+    /// * `page_token` - Token from a previous response to fetch the next page
+    ///
+    /// # Returns
+    ///
+    /// Returns the models on the page and the token for the next page.
+    pub async fn list_models(
+        &self,
+        search: &str,
+        owner: Option<&str>,
+        sort_by: &str,
+        page_size: i32,
+        page_token: Option<&str>,
+    ) -> Result<ModelListResponse, Error> {
+        let mut query_params = vec![
+            format!("sortBy={}", urlencoding::encode(sort_by)),
+            format!("pageSize={}", page_size),
+        ];
+        if !search.is_empty() {
+            query_params.push(format!("search={}", urlencoding::encode(search)));
+        }
+        if let Some(owner) = owner {
+            query_params.push(format!("owner={}", urlencoding::encode(owner)));
+        }
+        if let Some(token) = page_token {
+            query_params.push(format!("pageToken={}", urlencoding::encode(token)));
+        }
+        let url = format!("{}?{}", self.endpoint("/models/list"), query_params.join("&"));
+
+        debug!("Fetching models from: {}", url);
+
+        let response = self.request(self.http_client().get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Gets the details of a model.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The model
+    ///
+    /// # Returns
+    ///
+    /// Returns the model including its description, tags and instances.
+    pub async fn get_model(&self, model: &ModelRef) -> Result<Model, Error> {
+        let url = self.endpoint(&format!("{}/get", model_path(model)));
+
+        debug!("Fetching model from: {}", url);
+
+        let response = self.request(self.http_client().get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Writes a `model-metadata.json` template to a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder` - Directory to write the template to
+    ///
+    /// # Returns
+    ///
+    /// Returns the path of the written file, or `Error::InvalidParameter` if
+    /// the file already exists.
+    pub async fn initialize_model_metadata(&self, folder: &Path) -> Result<PathBuf, Error> {
+        write_metadata_template(folder, MODEL_METADATA_FILE, &ModelMetadata::template()).await
+    }
+
+    /// Creates a new model from the `model-metadata.json` in a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder` - Directory containing `model-metadata.json`
+    ///
+    /// # Returns
+    ///
+    /// Returns the created model's reference and URL.
+    pub async fn create_model(&self, folder: &Path) -> Result<ModelResponse, Error> {
+        let metadata = read_model_metadata(folder).await?;
+        let model_ref: ModelRef = metadata.model_ref().parse()?;
+        let url = self.endpoint(&format!(
+            "/models/{}/create/new",
+            urlencoding::encode(&model_ref.owner)
+        ));
+
+        info!("Creating model {}", model_ref);
+        debug!("Creating model at: {}", url);
+
+        let response = self
+            .request(self.http_client().post(&url).json(&metadata))
+            .await?;
+        check_model_response(response.json().await?, "create model")
+    }
+
+    /// Updates a model from the `model-metadata.json` in a directory.
+    ///
+    /// Only fields that differ from the current model are sent, so fields
+    /// edited on the website since the metadata was written are preserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder` - Directory containing `model-metadata.json`
+    ///
+    /// # Returns
+    ///
+    /// Returns the updated model's reference and URL, or
+    /// `Error::InvalidParameter` if nothing changed.
+    pub async fn update_model(&self, folder: &Path) -> Result<ModelResponse, Error> {
+        let metadata = read_model_metadata(folder).await?;
+        let model_ref: ModelRef = metadata.model_ref().parse()?;
+        let current = self.get_model(&model_ref).await?;
+
+        let changed = metadata.changed_fields(&current);
+        if changed.is_empty() {
+            return Err(Error::InvalidParameter(format!(
+                "{} has no changes compared to {}",
+                MODEL_METADATA_FILE, model_ref
+            )));
+        }
+
+        let url = self.endpoint(&format!("{}/update", model_path(&model_ref)));
+        let request = ModelUpdateRequest {
+            metadata,
+            update_mask: changed.join(","),
+        };
+
+        info!("Updating model {} ({})", model_ref, request.update_mask);
+        debug!("Updating model at: {}", url);
+
+        let response = self
+            .request(self.http_client().post(&url).json(&request))
+            .await?;
+        check_model_response(response.json().await?, "update model")
+    }
+
+    /// Deletes a model and all of its instances.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The model
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok` if the model was deleted.
+    pub async fn delete_model(&self, model: &ModelRef) -> Result<ModelResponse, Error> {
+        let url = self.endpoint(&format!("{}/delete", model_path(model)));
+
+        info!("Deleting model {}", model);
+
+        let response = self.request(self.http_client().post(&url)).await?;
+        check_model_response(response.json().await?, "delete model")
+    }
+
+    /// Gets the details of a model instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The model instance; a version in the reference is ignored
+    ///
+    /// # Returns
+    ///
+    /// Returns the instance including its framework, license and usage notes.
+    pub async fn get_model_instance(&self, instance: &ModelInstanceRef) -> Result<ModelInstance, Error> {
+        let url = self.endpoint(&format!("{}/get", instance_path(instance)));
+
+        debug!("Fetching model instance from: {}", url);
+
+        let response = self.request(self.http_client().get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Writes a `model-instance-metadata.json` template to a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder` - Directory to write the template to
+    ///
+    /// # Returns
+    ///
+    /// Returns the path of the written file, or `Error::InvalidParameter` if
+    /// the file already exists.
+    pub async fn initialize_model_instance_metadata(&self, folder: &Path) -> Result<PathBuf, Error> {
+        write_metadata_template(
+            folder,
+            MODEL_INSTANCE_METADATA_FILE,
+            &ModelInstanceMetadata::template(),
+        )
+        .await
+    }
+
+    /// Creates a new instance of an existing model from the
+    /// `model-instance-metadata.json` in a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder` - Directory containing `model-instance-metadata.json`
+    ///
+    /// # Returns
+    ///
+    /// Returns the created instance's reference and URL.
+    pub async fn create_model_instance(&self, folder: &Path) -> Result<ModelResponse, Error> {
+        let metadata = read_model_instance_metadata(folder).await?;
+        let instance_ref: ModelInstanceRef = metadata.instance_ref().parse()?;
+        let url = self.endpoint(&format!(
+            "{}/create/instance",
+            model_path(&instance_ref.model_ref())
+        ));
+
+        info!("Creating model instance {}", instance_ref);
+        debug!("Creating model instance at: {}", url);
+
+        let response = self
+            .request(self.http_client().post(&url).json(&metadata))
+            .await?;
+        check_model_response(response.json().await?, "create model instance")
+    }
+
+    /// Updates a model instance from the `model-instance-metadata.json` in a
+    /// directory.
+    ///
+    /// Only fields that differ from the current instance are sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `folder` - Directory containing `model-instance-metadata.json`
+    ///
+    /// # Returns
+    ///
+    /// Returns the updated instance's reference and URL, or
+    /// `Error::InvalidParameter` if nothing changed.
+    pub async fn update_model_instance(&self, folder: &Path) -> Result<ModelResponse, Error> {
+        let metadata = read_model_instance_metadata(folder).await?;
+        let instance_ref: ModelInstanceRef = metadata.instance_ref().parse()?;
+        let current = self.get_model_instance(&instance_ref).await?;
+
+        let changed = metadata.changed_fields(&current);
+        if changed.is_empty() {
+            return Err(Error::InvalidParameter(format!(
+                "{} has no changes compared to {}",
+                MODEL_INSTANCE_METADATA_FILE, instance_ref
+            )));
+        }
+
+        let url = self.endpoint(&format!("{}/update", instance_path(&instance_ref)));
+        let request = ModelInstanceUpdateRequest {
+            metadata,
+            update_mask: changed.join(","),
+        };
+
+        info!("Updating model instance {} ({})", instance_ref, request.update_mask);
+        debug!("Updating model instance at: {}", url);
+
+        let response = self
+            .request(self.http_client().post(&url).json(&request))
+            .await?;
+        check_model_response(response.json().await?, "update model instance")
+    }
+
+    /// Deletes a model instance and all of its versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The model instance, without a version
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok` if the instance was deleted, or `Error::InvalidParameter`
+    /// if the reference includes a version.
+    pub async fn delete_model_instance(&self, instance: &ModelInstanceRef) -> Result<ModelResponse, Error> {
+        if instance.version.is_some() {
+            return Err(Error::InvalidParameter(format!(
+                "Cannot delete {}: the reference names a single version, but deleting removes the whole instance",
+                instance
+            )));
+        }
+        let url = self.endpoint(&format!("{}/delete", instance_path(instance)));
+
+        info!("Deleting model instance {}", instance);
+
+        let response = self.request(self.http_client().post(&url)).await?;
+        check_model_response(response.json().await?, "delete model instance")
+    }
+
+    /// Uploads the files in a directory as a new version of a model instance.
+    ///
+    /// Hidden files and a top-level `model-instance-metadata.json` are not
+    /// uploaded; subdirectories are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The model instance; a version in the reference is ignored
+    /// * `folder` - Directory containing the files of the version
+    /// * `version_notes` - Notes describing the version
+    ///
+    /// # Returns
+    ///
+    /// Returns the new version's reference and URL.
+    pub async fn create_model_instance_version(
+        &self,
+        instance: &ModelInstanceRef,
+        folder: &Path,
+        version_notes: &str,
+    ) -> Result<ModelResponse, Error> {
+        let url = self.endpoint(&format!("{}/create/version", instance_path(instance)));
+        let tree = self
+            .upload_directory(folder, "model", &[MODEL_INSTANCE_METADATA_FILE])
+            .await?;
+        let request = ModelInstanceVersionRequest {
+            version_notes: version_notes.to_string(),
+            files: tree.files,
+            directories: tree.directories,
+        };
+
+        info!("Creating new version of model instance {}", instance);
+        debug!("Creating model instance version at: {}", url);
+
+        let response = self
+            .request(self.http_client().post(&url).json(&request))
+            .await?;
+        check_model_response(response.json().await?, "create model instance version")
+    }
+
+    /// Lists the versions of a model instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The model instance; a version in the reference is ignored
+    /// * `page_size` - Number of versions per page
+    /// * `page_token` - Token from a previous response to fetch the next page
+    ///
+    /// # Returns
+    ///
+    /// Returns the versions on the page, newest first, and the token for the
+    /// next page.
+    pub async fn list_model_instance_versions(
+        &self,
+        instance: &ModelInstanceRef,
+        page_size: i32,
+        page_token: Option<&str>,
+    ) -> Result<ModelInstanceVersionListResponse, Error> {
+        let mut url = format!(
+            "{}?pageSize={}",
+            self.endpoint(&format!("{}/versions/list", instance_path(instance))),
+            page_size
+        );
+        if let Some(token) = page_token {
+            url.push_str(&format!("&pageToken={}", urlencoding::encode(token)));
+        }
+
+        debug!("Fetching model instance versions from: {}", url);
+
+        let response = self.request(self.http_client().get(&url)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Downloads a version of a model instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The model instance; the version in the reference is
+    ///   downloaded, or the latest version if it has none
+    /// * `path` - Directory to download to
+    /// * `extract` - Whether to extract the downloaded archive and remove it
+    ///
+    /// # Returns
+    ///
+    /// Returns the directory the files were extracted to, or the path of the
+    /// archive if `extract` is false.
+    pub async fn download_model_instance_version(
+        &self,
+        instance: &ModelInstanceRef,
+        path: &Path,
+        extract: bool,
+    ) -> Result<PathBuf, Error> {
+        let version = match instance.version {
+            Some(version) => version,
+            None => self
+                .get_model_instance(instance)
+                .await?
+                .version_number
+                .ok_or_else(|| {
+                    Error::InvalidParameter(format!("Model instance {} has no versions", instance))
+                })?,
+        };
+
+        let url = self.endpoint(&format!("{}/{}/download", instance_path(instance), version));
+        let archive = path.join(format!("{}-v{}.tar.gz", instance.variation, version));
+
+        info!("Downloading version {} of model instance {}", version, instance);
+        self.download_file(&url, &archive).await?;
+
+        if !extract {
+            return Ok(archive);
+        }
+        extract_archive(&archive, path).await?;
+        tokio::fs::remove_file(&archive).await?;
+        Ok(path.to_path_buf())
+    }
+}
+
+/// Returns the API path of a model, without the action suffix.
+fn model_path(model: &ModelRef) -> String {
+    format!(
+        "/models/{}/{}",
+        urlencoding::encode(&model.owner),
+        urlencoding::encode(&model.slug)
+    )
+}
+
+/// Returns the API path of a model instance, without the version or action
+/// suffix.
+fn instance_path(instance: &ModelInstanceRef) -> String {
+    format!(
+        "/models/{}/{}/{}/{}",
+        urlencoding::encode(&instance.owner),
+        urlencoding::encode(&instance.model),
+        instance.framework,
+        urlencoding::encode(&instance.variation)
+    )
+}
+
+/// Reads and validates the `model-metadata.json` in a directory.
+pub(crate) async fn read_model_metadata(folder: &Path) -> Result<ModelMetadata, Error> {
+    let path = folder.join(MODEL_METADATA_FILE);
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| Error::InvalidParameter(format!("Cannot read {:?}: {}", path, e)))?;
+    let metadata: ModelMetadata = serde_json::from_str(&content)?;
+    metadata.validate()?;
+    Ok(metadata)
+}
+
+/// Reads and validates the `model-instance-metadata.json` in a directory.
+pub(crate) async fn read_model_instance_metadata(folder: &Path) -> Result<ModelInstanceMetadata, Error> {
+    let path = folder.join(MODEL_INSTANCE_METADATA_FILE);
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| Error::InvalidParameter(format!("Cannot read {:?}: {}", path, e)))?;
+    let metadata: ModelInstanceMetadata = serde_json::from_str(&content)?;
+    metadata.validate()?;
+    Ok(metadata)
+}
+
+/// Writes a metadata template, refusing to overwrite an existing file.
+pub(crate) async fn write_metadata_template<T: serde::Serialize>(
+    folder: &Path,
+    file_name: &str,
+    template: &T,
+) -> Result<PathBuf, Error> {
+    let path = folder.join(file_name);
+    if tokio::fs::try_exists(&path).await? {
+        return Err(Error::InvalidParameter(format!(
+            "{:?} already exists",
+            path
+        )));
+    }
+    tokio::fs::create_dir_all(folder).await?;
+    tokio::fs::write(&path, serde_json::to_string_pretty(template)?).await?;
+    info!("Wrote metadata template to {:?}", path);
+    Ok(path)
+}
+
+/// Turns an error reported in a model response body into an `Error`.
+pub(crate) fn check_model_response(response: ModelResponse, action: &str) -> Result<ModelResponse, Error> {
+    match response.error {
+        Some(ref error) if !error.is_empty() => {
+            Err(Error::Other(format!("Failed to {}: {}", action, error)))
+        }
+        _ => Ok(response),
+    }
+}
@@ -0,0 +1,173 @@
+//! Named credential profiles (`profile_list`, `profile_use`).
+//!
+//! Profiles live in `kaggle.json` next to the default credentials:
+//!
+//! ```json
+//! {
+//!   "username": "me",
+//!   "key": "...",
+//!   "profiles": {
+//!     "team-bot": { "username": "team-bot", "key": "..." },
+//!     "new-member": { "token": "..." }
+//!   }
+//! }
+//! ```
+//!
+//! The top-level `username` and `key` form the `default` profile, so the file
+//! stays readable by the official Kaggle CLI. With an encrypted credentials
+//! file, the same structure is kept there instead.
+
+use super::{read_credentials_json, KaggleClient, Session};
+use crate::models::{CredentialProfile, CredentialSource, Error, KaggleAuth};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
+
+/// Name of the profile formed by the top-level `username` and `key`.
+pub const DEFAULT_PROFILE: &str = "default";
+
+impl KaggleClient {
+    /// Lists the profiles in the credentials store.
+    ///
+    /// # Returns
+    ///
+    /// Returns each profile's name and username, and which one is active.
+    pub async fn list_profiles(&self) -> Result<Vec<CredentialProfile>, Error> {
+        let active = self.session.read().await.profile.clone();
+        let profiles = read_profiles().await?;
+        Ok(profiles
+            .into_iter()
+            .map(|(name, credentials)| CredentialProfile {
+                active: active.as_deref() == Some(name.as_str()),
+                name,
+                username: credentials.username().map(str::to_string),
+                scheme: credentials.scheme().to_string(),
+            })
+            .collect())
+    }
+
+    /// Makes a profile's credentials the ones used for requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Profile name, or `default` for the top-level credentials
+    ///
+    /// # Returns
+    ///
+    /// Returns the profile, or `Error::InvalidParameter` if there is no such
+    /// profile.
+    pub async fn use_profile(&self, name: &str) -> Result<CredentialProfile, Error> {
+        let credentials = find_profile(name).await?;
+        let profile = CredentialProfile {
+            name: name.to_string(),
+            username: credentials.username().map(str::to_string),
+            scheme: credentials.scheme().to_string(),
+            active: true,
+        };
+        let session = Session::from_source(CredentialSource::Profile, Some(name.to_string()));
+        self.set_credentials(credentials, session).await;
+        info!("Switched to profile {}", name);
+        Ok(profile)
+    }
+
+    /// Returns a client for a single call with a profile's credentials.
+    ///
+    /// The returned client shares the HTTP client and configuration with this
+    /// one, but switching its profile does not affect this client. With no
+    /// profile, it shares this client's credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - Profile to use, or `None` for the active one
+    pub async fn for_profile(&self, profile: Option<&str>) -> Result<KaggleClient, Error> {
+        let (credentials, session) = match profile {
+            Some(name) => (
+                Arc::new(RwLock::new(Some(find_profile(name).await?))),
+                Arc::new(RwLock::new(Session::from_source(
+                    CredentialSource::Profile,
+                    Some(name.to_string()),
+                ))),
+            ),
+            None => (self.credentials.clone(), self.session.clone()),
+        };
+        Ok(KaggleClient {
+            http_client: self.http_client.clone(),
+            credentials,
+            session,
+            config: self.config.clone(),
+            config_layers: self.config_layers.clone(),
+            api_base: self.api_base.clone(),
+            api_base_override: self.api_base_override.clone(),
+            retry_policy_override: self.retry_policy_override.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            cache: self.cache.clone(),
+            #[cfg(test)]
+            skip_save_credentials: self.skip_save_credentials,
+        })
+    }
+}
+
+/// Reads every profile in `kaggle.json` (or the encrypted credentials file),
+/// including `default` if the file has top-level credentials. A profile holds
+/// either a `username` and `key` or an access `token`.
+pub(crate) async fn read_profiles() -> Result<BTreeMap<String, KaggleAuth>, Error> {
+    let mut json = read_credentials_json().await?;
+    let mut profiles: BTreeMap<String, KaggleAuth> = match json.remove("profiles") {
+        Some(profiles) => serde_json::from_value(profiles)
+            .map_err(|e| Error::Other(format!("Invalid profiles in kaggle.json: {}", e)))?,
+        None => BTreeMap::new(),
+    };
+    if let Ok(credentials) = serde_json::from_value(json.into()) {
+        profiles.insert(DEFAULT_PROFILE.to_string(), credentials);
+    }
+    Ok(profiles)
+}
+
+/// Looks up one profile in `kaggle.json`.
+async fn find_profile(name: &str) -> Result<KaggleAuth, Error> {
+    let mut profiles = read_profiles().await?;
+    profiles.remove(name).ok_or_else(|| {
+        let names: Vec<_> = profiles.into_keys().collect();
+        Error::InvalidParameter(format!(
+            "Unknown profile '{}' (available: {})",
+            name,
+            if names.is_empty() { "none".to_string() } else { names.join(", ") }
+        ))
+    })
+}
+
+/// Fails if a profile already holds credentials for another username, so
+/// saving would clobber someone else's credentials.
+pub(crate) async fn check_overwrite(profile: &str, username: &str) -> Result<(), Error> {
+    match read_profiles().await?.get(profile) {
+        Some(existing) if existing.username() != Some(username) => {
+            Err(Error::InvalidParameter(format!(
+                "Profile '{}' in kaggle.json holds credentials for {}, not '{}'. \
+                 Use another profile, or force to overwrite them",
+                profile,
+                existing
+                    .username()
+                    .map_or_else(|| "an access token".to_string(), |name| format!("'{}'", name)),
+                username
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a profile name can be stored.
+pub(crate) fn validate_profile_name(name: &str) -> Result<(), Error> {
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!(
+            "Invalid profile name '{}' (use letters, digits, '-', '_' or '.')",
+            name
+        )))
+    }
+}
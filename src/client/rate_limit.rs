@@ -0,0 +1,95 @@
+//! Client-side rate limiting of API requests.
+//!
+//! Every request to the Kaggle API, including retries, takes a token from a
+//! bucket shared by all tool calls. The bucket refills at the configured
+//! `requests_per_minute` and holds at most a tenth of a minute's budget, so
+//! bursts stay short. When it is empty, requests wait their turn.
+
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+/// Requests per minute unless `requests_per_minute` is configured.
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 60;
+
+/// A token bucket that requests wait on in arrival order.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    bucket: Mutex<TokenBucket>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `requests_per_minute`; 0 means unlimited.
+    pub(crate) fn new(requests_per_minute: u32) -> Self {
+        Self {
+            bucket: Mutex::new(TokenBucket::new(requests_per_minute, Instant::now())),
+        }
+    }
+
+    /// Changes the budget, keeping the tokens already in the bucket.
+    pub(crate) async fn set_rate(&self, requests_per_minute: u32) {
+        let mut bucket = self.bucket.lock().await;
+        if bucket.requests_per_minute != requests_per_minute {
+            let tokens = bucket.tokens;
+            *bucket = TokenBucket::new(requests_per_minute, Instant::now());
+            bucket.tokens = tokens.min(bucket.capacity);
+        }
+    }
+
+    /// Waits until a request may be sent.
+    pub(crate) async fn acquire(&self) {
+        // The lock is held while waiting, so waiters are served in order.
+        let mut bucket = self.bucket.lock().await;
+        let wait = bucket.take(Instant::now());
+        if !wait.is_zero() {
+            debug!("Rate limit reached; waiting {:.1}s", wait.as_secs_f64());
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Token bucket state.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    requests_per_minute: u32,
+    capacity: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket.
+    pub(crate) fn new(requests_per_minute: u32, now: Instant) -> Self {
+        let capacity = (f64::from(requests_per_minute) / 10.0).max(1.0);
+        Self {
+            requests_per_minute,
+            capacity,
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    /// Takes a token for a request made at `now`.
+    ///
+    /// # Returns
+    ///
+    /// Returns how long to wait before sending the request; zero if a token
+    /// was available.
+    pub(crate) fn take(&mut self, now: Instant) -> Duration {
+        if self.requests_per_minute == 0 {
+            return Duration::ZERO;
+        }
+        let per_second = f64::from(self.requests_per_minute) / 60.0;
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second).min(self.capacity);
+        self.updated = now;
+
+        // The token may be borrowed from the future; waiting pays it back.
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / per_second)
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Retries of API requests that fail transiently.
+//!
+//! Requests answered with 408, 429, 500, 502, 503 or 504, or that fail with a
+//! connection error or timeout, are sent again after an exponential backoff
+//! with jitter, or after the server's `Retry-After`. Requests that are not
+//! idempotent (e.g. `POST` submissions) are only retried when the server
+//! cannot have acted on them: on 429, or when no connection was made.
+
+use super::KaggleClient;
+use reqwest::{Method, Request, Response, StatusCode};
+use std::time::Duration;
+use tracing::warn;
+
+/// How API requests that fail transiently are retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per request, including the first; 1 disables retries
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub base_delay: Duration,
+    /// Longest delay between attempts. A longer `Retry-After` is not waited
+    /// for; the response is returned as is.
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Attempts per request unless `max_attempts` is configured.
+    pub const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+
+    /// Returns the delay before retry number `retry` (starting at 1): the
+    /// exponential backoff, of which a random half is jitter.
+    fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = backoff / 2;
+        half + half.mul_f64(rand::random::<f64>())
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: Self::DEFAULT_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl KaggleClient {
+    /// Returns the retry policy requests are sent with.
+    ///
+    /// This is the policy given to [`Self::with_retry_policy`], else the
+    /// default policy with the configured `max_attempts`.
+    pub async fn retry_policy(&self) -> RetryPolicy {
+        match self.retry_policy_override {
+            Some(ref policy) => policy.clone(),
+            None => RetryPolicy {
+                max_attempts: self
+                    .config
+                    .read()
+                    .await
+                    .max_attempts
+                    .unwrap_or(RetryPolicy::DEFAULT_MAX_ATTEMPTS),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Retries requests according to `policy` regardless of the configured
+    /// `max_attempts`.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy_override = Some(policy);
+        self
+    }
+
+    /// Sends a request, retrying transient failures. Each attempt waits for
+    /// the rate limiter.
+    ///
+    /// # Returns
+    ///
+    /// Returns the last response, which may still be an error status, or the
+    /// last connection error.
+    pub(crate) async fn send_with_retries(
+        &self,
+        client: &reqwest::Client,
+        request: Request,
+    ) -> Result<Response, reqwest::Error> {
+        let policy = self.retry_policy().await;
+        let idempotent = is_idempotent(request.method());
+        let mut attempt = 1;
+        loop {
+            // Streaming bodies cannot be replayed, so they get a single attempt.
+            let next = match request.try_clone() {
+                Some(next) if attempt < policy.max_attempts => next,
+                _ => {
+                    self.rate_limiter.acquire().await;
+                    return client.execute(request).await;
+                }
+            };
+            self.rate_limiter.acquire().await;
+            let (delay, reason) = match client.execute(next).await {
+                Ok(response) => {
+                    let status = response.status();
+                    if !is_retryable_status(status) || !(idempotent || status == StatusCode::TOO_MANY_REQUESTS) {
+                        return Ok(response);
+                    }
+                    let delay = match retry_after(&response) {
+                        Some(delay) if delay > policy.max_delay => return Ok(response),
+                        Some(delay) => delay,
+                        None => policy.backoff(attempt),
+                    };
+                    (delay, status.to_string())
+                }
+                Err(e) if e.is_connect() || (idempotent && (e.is_timeout() || e.is_request())) => {
+                    (policy.backoff(attempt), e.to_string())
+                }
+                Err(e) => return Err(e),
+            };
+            warn!(
+                "{} {} failed ({}); retrying in {:.1}s (attempt {} of {})",
+                request.method(),
+                request.url().path(),
+                reason,
+                delay.as_secs_f64(),
+                attempt + 1,
+                policy.max_attempts
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Returns whether sending a request twice has the same effect as once.
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+/// Returns whether a status code indicates a failure worth retrying.
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Reads a `Retry-After` header given in seconds or as an HTTP date.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default())
+}
@@ -0,0 +1,137 @@
+//! Active account introspection (`whoami`) and `logout`.
+
+use super::{
+    access_token_path, credential_store, encrypted_credentials_path, kaggle_json_path,
+    read_credentials_json, update_credentials_json, KaggleClient, DEFAULT_PROFILE,
+};
+use crate::models::{CredentialSource, Error, Identity};
+use tracing::{info, warn};
+
+impl KaggleClient {
+    /// Describes the account requests are made with.
+    ///
+    /// # Arguments
+    ///
+    /// * `verify` - Check the credentials against the API first
+    ///
+    /// # Returns
+    ///
+    /// Returns the username, authentication scheme, credential source and
+    /// profile, and whether the API has accepted the credentials. A failed
+    /// verification is reported as `verified: false` rather than an error.
+    pub async fn whoami(&self, verify: bool) -> Result<Identity, Error> {
+        let credentials = self.credentials.read().await.clone();
+        let Some(credentials) = credentials else {
+            return Ok(Identity {
+                authenticated: false,
+                username: None,
+                scheme: None,
+                source: None,
+                profile: None,
+                verified: false,
+            });
+        };
+
+        if verify {
+            let verified = match self.verify_credentials(&credentials).await {
+                Ok(()) => true,
+                Err(Error::AuthenticationError(e)) => {
+                    warn!("Credentials were rejected: {}", e);
+                    false
+                }
+                Err(e) => return Err(e),
+            };
+            self.session.write().await.verified = verified;
+        }
+
+        let session = self.session.read().await.clone();
+        Ok(Identity {
+            authenticated: true,
+            username: credentials.username().map(str::to_string),
+            scheme: Some(credentials.scheme().to_string()),
+            source: session.source,
+            profile: session.profile,
+            verified: session.verified,
+        })
+    }
+
+    /// Forgets the active credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `delete_persisted` - Also delete the saved copy of the credentials:
+    ///   the active profile's entry in `kaggle.json` (or the encrypted
+    ///   credentials file) or the access token file. Configuration values and
+    ///   other profiles are kept.
+    ///
+    /// # Returns
+    ///
+    /// Returns the files or entries that were deleted.
+    pub async fn logout(&self, delete_persisted: bool) -> Result<Vec<String>, Error> {
+        let session = std::mem::take(&mut *self.session.write().await);
+        self.credentials.write().await.take();
+        info!("Logged out");
+
+        if !delete_persisted {
+            return Ok(Vec::new());
+        }
+
+        let mut deleted = Vec::new();
+        if session.source == Some(CredentialSource::AccessTokenFile) {
+            if let Some(path) = access_token_path() {
+                tokio::fs::remove_file(&path).await?;
+                deleted.push(path.display().to_string());
+            }
+        } else if let Some(profile) = session.profile {
+            if let Some(entry) = delete_profile(&profile).await? {
+                deleted.push(entry);
+            }
+        }
+        Ok(deleted)
+    }
+}
+
+/// Removes a profile's credentials from `kaggle.json`, deleting the file if
+/// nothing else is left in it, or from the encrypted credentials file.
+///
+/// # Returns
+///
+/// Returns a description of what was deleted, or `None` if the profile was
+/// not saved.
+async fn delete_profile(profile: &str) -> Result<Option<String>, Error> {
+    let encrypted = credential_store::is_enabled();
+    let path = if encrypted { encrypted_credentials_path() } else { kaggle_json_path() };
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let mut json = read_credentials_json().await?;
+    let removed = if profile == DEFAULT_PROFILE {
+        let removed = ["username", "key", "token"]
+            .iter()
+            .filter(|field| json.remove(**field).is_some())
+            .count();
+        removed > 0
+    } else {
+        let profiles = json.get_mut("profiles").and_then(|p| p.as_object_mut());
+        let removed = profiles.is_some_and(|profiles| profiles.remove(profile).is_some());
+        if json.get("profiles").and_then(|p| p.as_object()).is_some_and(|p| p.is_empty()) {
+            json.remove("profiles");
+        }
+        removed
+    };
+    if !removed {
+        return Ok(None);
+    }
+
+    // An empty encrypted file is kept, so credentials saved later are still
+    // encrypted.
+    if json.is_empty() && !encrypted {
+        tokio::fs::remove_file(&path).await?;
+        info!("Deleted {:?}", path);
+        Ok(Some(path.display().to_string()))
+    } else {
+        update_credentials_json(false, |stored| *stored = json).await?;
+        info!("Deleted profile {} from {:?}", profile, path);
+        Ok(Some(format!("profile {} in {}", profile, path.display())))
+    }
+}
@@ -1,18 +1,36 @@
+#![allow(clippy::module_inception)]
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
-    use mockito::{Server, ServerGuard};
+    use crate::models::{
+        AccessToken, CacheMode, CompetitionRef, ConfigLayer, CredentialSource, KernelMetadata,
+        KernelPushOptions, KernelRef, KernelSourceFormat, ModelInstanceRef, ModelRef,
+    };
+    use mockito::{Matcher, Server, ServerGuard};
     use tempfile::TempDir;
     use serial_test::serial;
 
     async fn create_test_client() -> (KaggleClient, ServerGuard) {
         let server = Server::new_async().await;
         let client = KaggleClient::new()
-            .with_api_base(server.url())
+            .with_api_base(format!("{}/api/v1", server.url()))
             .skip_save_credentials();
         (client, server)
     }
 
+    async fn create_authenticated_client() -> (KaggleClient, ServerGuard) {
+        let (client, server) = create_test_client().await;
+        {
+            let mut creds = client.credentials.write().await;
+            *creds = Some(KaggleCredentials {
+                username: "test_user".to_string(),
+                key: "test_key".into(),
+            }.into());
+        }
+        (client, server)
+    }
+
     #[tokio::test]
     async fn test_new_client() {
         let (client, _server) = create_test_client().await;
@@ -174,11 +192,1471 @@ mod tests {
             let mut creds = client.credentials.write().await;
             *creds = Some(KaggleCredentials {
                 username: "test".to_string(),
-                key: "test_key".to_string(),
-            });
+                key: "test_key".into(),
+            }.into());
         }
         
         // Now should be authenticated
         assert!(client.is_authenticated().await);
     }
-}
\ No newline at end of file
+
+    const PULLED_NOTEBOOK: &str = r#"{
+        "metadata": {"id": 42, "ref": "test_user/titanic-eda", "title": "Titanic EDA",
+                     "language": "python", "kernelType": "notebook", "isPrivate": true,
+                     "datasetDataSources": ["owner/data"]},
+        "blob": {"source": "{\"cells\": [{\"cell_type\": \"code\", \"metadata\": {}, \"source\": \"print(1)\", \"outputs\": [], \"execution_count\": null}], \"metadata\": {}, \"nbformat\": 4, \"nbformat_minor\": 4}",
+                 "language": "python", "kernelType": "notebook"}
+    }"#;
+
+    #[tokio::test]
+    async fn test_pull_kernel_as_script() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _m = server.mock("GET", "/api/v1/kernels/pull")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("userName".into(), "test_user".into()),
+                Matcher::UrlEncoded("kernelSlug".into(), "titanic-eda".into()),
+            ]))
+            .with_status(200)
+            .with_body(PULLED_NOTEBOOK)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        let pulled = client
+            .pull_kernel_to(&"test_user/titanic-eda".parse().unwrap(), dir.path(), KernelSourceFormat::Script, true)
+            .await
+            .unwrap();
+
+        assert_eq!(pulled.source_file, dir.path().join("titanic-eda.py"));
+        let script = std::fs::read_to_string(&pulled.source_file).unwrap();
+        assert_eq!(script, "# %%\nprint(1)\n");
+
+        let metadata: KernelMetadata = serde_json::from_str(
+            &std::fs::read_to_string(pulled.metadata_file.unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata.id, "test_user/titanic-eda");
+        assert_eq!(metadata.code_file, "titanic-eda.py");
+        assert_eq!(metadata.kernel_type, "notebook");
+        assert_eq!(metadata.dataset_sources, vec!["owner/data".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_pull_kernel_invalid_ref() {
+        let result = "not-a-ref".parse::<KernelRef>();
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    }
+
+    #[tokio::test]
+    async fn test_push_kernel_converts_script_to_notebook() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _m = server.mock("POST", "/api/v1/kernels/push")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "slug": "test_user/titanic-eda",
+                "kernelType": "notebook",
+            })))
+            .with_status(200)
+            .with_body(r#"{"ref": "test_user/titanic-eda", "url": "https://www.kaggle.com/code/test_user/titanic-eda", "versionNumber": 3}"#)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("main.py"), "# %% [markdown]\n# Hi\n\n# %%\nx = 1\n").unwrap();
+        std::fs::write(
+            dir.path().join("kernel-metadata.json"),
+            r#"{"id": "test_user/titanic-eda", "title": "Titanic EDA", "code_file": "main.py",
+                "language": "python", "kernel_type": "notebook"}"#,
+        )
+        .unwrap();
+
+        let response = client.push_kernel_from(dir.path(), &KernelPushOptions::default()).await.unwrap();
+        assert_eq!(response.version_number, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_push_kernel_reports_rejection() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _m = server.mock("POST", "/api/v1/kernels/push")
+            .with_status(200)
+            .with_body(r#"{"error": "Invalid dataset sources"}"#)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("main.py"), "print(1)\n").unwrap();
+        std::fs::write(
+            dir.path().join("kernel-metadata.json"),
+            r#"{"id": "test_user/x", "title": "X", "code_file": "main.py",
+                "language": "python", "kernel_type": "script"}"#,
+        )
+        .unwrap();
+
+        let result = client.push_kernel_from(dir.path(), &KernelPushOptions::default()).await;
+        match result {
+            Err(Error::Other(msg)) => assert!(msg.contains("Invalid dataset sources")),
+            _ => panic!("Expected push rejection"),
+        }
+    }
+
+    fn pulled_notebook_version(source: &str) -> String {
+        let notebook = serde_json::json!({
+            "cells": [{"cell_type": "code", "metadata": {}, "source": source,
+                       "outputs": [], "execution_count": 1}],
+            "metadata": {}, "nbformat": 4, "nbformat_minor": 4
+        });
+        serde_json::json!({
+            "metadata": {"ref": "test_user/model", "title": "Model"},
+            "blob": {"source": notebook.to_string(), "language": "python", "kernelType": "notebook"}
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_diff_kernel_versions() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _v1 = server.mock("GET", "/api/v1/kernels/pull")
+            .match_query(Matcher::UrlEncoded("versionNumber".into(), "12".into()))
+            .with_status(200)
+            .with_body(pulled_notebook_version("lr = 0.1\nfit(lr)"))
+            .create_async()
+            .await;
+        let _v2 = server.mock("GET", "/api/v1/kernels/pull")
+            .match_query(Matcher::UrlEncoded("versionNumber".into(), "15".into()))
+            .with_status(200)
+            .with_body(pulled_notebook_version("lr = 0.3\nfit(lr)"))
+            .create_async()
+            .await;
+
+        let diff = client.diff_kernel_versions(&"test_user/model".parse().unwrap(), 12, 15).await.unwrap();
+
+        assert!(diff.starts_with("--- test_user/model v12\n+++ test_user/model v15\n"));
+        assert!(diff.contains("-lr = 0.1\n+lr = 0.3\n"));
+        assert!(!diff.contains("execution_count"));
+    }
+
+    #[tokio::test]
+    async fn test_list_kernel_versions() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _m = server.mock("GET", "/api/v1/kernels/versions/list")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("kernelSlug".into(), "model".into()),
+                Matcher::UrlEncoded("pageSize".into(), "20".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"[{"versionNumber": 15, "title": "lr 0.3", "dateCreated": "2025-01-06T10:00:00Z",
+                           "status": "complete", "runTime": 812.5}]"#)
+            .create_async()
+            .await;
+
+        let versions = client.list_kernel_versions(&"test_user/model".parse().unwrap(), 1, 20).await.unwrap();
+
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version_number, 15);
+        assert_eq!(versions[0].status.as_deref(), Some("complete"));
+        assert_eq!(versions[0].run_time_seconds, Some(812.5));
+        assert!(versions[0].created_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_push_kernel_blocked_by_secrets() {
+        let (client, mut server) = create_authenticated_client().await;
+        let m = server.mock("POST", "/api/v1/kernels/push")
+            .with_status(200)
+            .with_body(r#"{"ref": "test_user/x", "versionNumber": 1}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("main.py"), "KAGGLE_KEY = 'test_key_in_source'\n").unwrap();
+        std::fs::write(
+            dir.path().join("kernel-metadata.json"),
+            r#"{"id": "test_user/x", "title": "X", "code_file": "main.py",
+                "language": "python", "kernel_type": "script"}"#,
+        )
+        .unwrap();
+        {
+            let mut creds = client.credentials.write().await;
+            *creds = Some(KaggleCredentials {
+                username: "test_user".to_string(),
+                key: "test_key_in_source".into(),
+            }.into());
+        }
+
+        let result = client.push_kernel_from(dir.path(), &KernelPushOptions::default()).await;
+        match result {
+            Err(Error::SecretsDetected(msg)) => {
+                assert!(msg.contains("script, line 1"));
+                assert!(!msg.contains("test_key_in_source"));
+            }
+            _ => panic!("Expected SecretsDetected error"),
+        }
+
+        let options = KernelPushOptions {
+            allow_secrets: true,
+            ..Default::default()
+        };
+        assert!(client.push_kernel_from(dir.path(), &options).await.is_ok());
+        m.assert_async().await;
+    }
+
+    async fn mock_code_competition_kernel(server: &mut ServerGuard, status: &str) -> Vec<mockito::Mock> {
+        let pull = server.mock("GET", "/api/v1/kernels/pull")
+            .match_query(Matcher::UrlEncoded("versionNumber".into(), "7".into()))
+            .with_status(200)
+            .with_body(r#"{"metadata": {"ref": "test_user/solution", "title": "Solution",
+                                        "competitionDataSources": ["llm-comp"], "currentVersionNumber": 7},
+                           "blob": {"source": "print(1)", "language": "python", "kernelType": "script"}}"#)
+            .create_async()
+            .await;
+        let versions = server.mock("GET", "/api/v1/kernels/versions/list")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(format!(r#"[{{"versionNumber": 8, "status": "running"}},
+                                   {{"versionNumber": 7, "status": "{}"}}]"#, status))
+            .create_async()
+            .await;
+        vec![pull, versions]
+    }
+
+    #[tokio::test]
+    async fn test_submit_kernel_version() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _mocks = mock_code_competition_kernel(&mut server, "complete").await;
+        let submit = server.mock("POST", "/api/v1/competitions/submissions/submit-notebook/llm-comp")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "competitionName": "llm-comp",
+                "kernelOwner": "test_user",
+                "kernelSlug": "solution",
+                "kernelVersion": 7,
+                "fileName": "submission.csv",
+            })))
+            .with_status(200)
+            .with_body(r#"{"message": "Successfully submitted", "ref": 123}"#)
+            .create_async()
+            .await;
+
+        let response = client
+            .submit_kernel_version(&"llm-comp".parse().unwrap(), &"test_user/solution".parse().unwrap(), 7, "submission.csv", "v7")
+            .await
+            .unwrap();
+
+        assert_eq!(response.message, "Successfully submitted");
+        assert_eq!(response.ref_, Some(123));
+        submit.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_submit_kernel_version_not_attached() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _mocks = mock_code_competition_kernel(&mut server, "complete").await;
+
+        let result = client
+            .submit_kernel_version(&"other-comp".parse().unwrap(), &"test_user/solution".parse().unwrap(), 7, "submission.csv", "v7")
+            .await;
+        match result {
+            Err(Error::InvalidParameter(msg)) => assert!(msg.contains("not attached")),
+            _ => panic!("Expected InvalidParameter error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_kernel_version_not_complete() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _mocks = mock_code_competition_kernel(&mut server, "error").await;
+
+        let result = client
+            .submit_kernel_version(&"llm-comp".parse().unwrap(), &"test_user/solution".parse().unwrap(), 7, "submission.csv", "v7")
+            .await;
+        match result {
+            Err(Error::InvalidParameter(msg)) => assert!(msg.contains("status: error")),
+            _ => panic!("Expected InvalidParameter error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_kernel_version_refuses_metadata_of_other_version() {
+        let (client, mut server) = create_authenticated_client().await;
+        // Kaggle answers with the metadata of the current version, 8.
+        let _pull = server.mock("GET", "/api/v1/kernels/pull")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"metadata": {"ref": "test_user/solution", "title": "Solution",
+                                        "competitionDataSources": ["llm-comp"], "currentVersionNumber": 8},
+                           "blob": {"source": "print(1)", "language": "python", "kernelType": "script"}}"#)
+            .create_async()
+            .await;
+        let submit = server.mock("POST", Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let result = client
+            .submit_kernel_version(&"llm-comp".parse().unwrap(), &"test_user/solution".parse().unwrap(), 7, "submission.csv", "v7")
+            .await;
+        match result {
+            Err(Error::InvalidParameter(msg)) => assert!(msg.contains("Cannot check")),
+            _ => panic!("Expected InvalidParameter error"),
+        }
+        submit.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_models() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _m = server.mock("GET", "/api/v1/models/list")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("search".into(), "gemma".into()),
+                Matcher::UrlEncoded("owner".into(), "google".into()),
+                Matcher::UrlEncoded("sortBy".into(), "voteCount".into()),
+                Matcher::UrlEncoded("pageToken".into(), "abc".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"models": [{"id": 1, "ref": "google/gemma", "title": "Gemma", "author": "google"}],
+                           "nextPageToken": "def"}"#)
+            .create_async()
+            .await;
+
+        let response = client
+            .list_models("gemma", Some("google"), "voteCount", 20, Some("abc"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.models.len(), 1);
+        assert_eq!(response.models[0].ref_, "google/gemma");
+        assert_eq!(response.next_page_token.as_deref(), Some("def"));
+    }
+
+    #[tokio::test]
+    async fn test_get_model() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _m = server.mock("GET", "/api/v1/models/google/gemma/get")
+            .with_status(200)
+            .with_body(r#"{"id": 1, "ref": "google/gemma", "title": "Gemma", "author": "google",
+                           "instances": [{"id": 2, "slug": "2b", "framework": "jax"}]}"#)
+            .create_async()
+            .await;
+
+        let model = client.get_model(&"google/gemma".parse().unwrap()).await.unwrap();
+        assert_eq!(model.instances[0].slug, "2b");
+
+        let result = "gemma".parse::<ModelRef>();
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    }
+
+    fn write_model_metadata(dir: &std::path::Path, description: &str) {
+        std::fs::write(
+            dir.join("model-metadata.json"),
+            serde_json::json!({
+                "ownerSlug": "test_user",
+                "title": "Baseline",
+                "slug": "baseline",
+                "subtitle": "",
+                "isPrivate": true,
+                "description": description,
+                "publishTime": "",
+                "provenanceSources": ""
+            })
+            .to_string(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_initialize_model_metadata() {
+        let client = KaggleClient::new();
+        let dir = TempDir::new().unwrap();
+
+        let path = client.initialize_model_metadata(dir.path()).await.unwrap();
+        let metadata: crate::models::ModelMetadata =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(metadata.validate().is_err());
+
+        // Refuses to overwrite an existing file
+        assert!(client.initialize_model_metadata(dir.path()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_model() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _m = server.mock("POST", "/api/v1/models/test_user/create/new")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "ownerSlug": "test_user",
+                "slug": "baseline",
+                "isPrivate": true,
+            })))
+            .with_status(200)
+            .with_body(r#"{"id": 9, "ref": "test_user/baseline", "url": "https://www.kaggle.com/models/test_user/baseline"}"#)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        write_model_metadata(dir.path(), "# Baseline");
+
+        let response = client.create_model(dir.path()).await.unwrap();
+        assert_eq!(response.ref_.as_deref(), Some("test_user/baseline"));
+    }
+
+    #[tokio::test]
+    async fn test_update_model_sends_changed_fields_only() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _get = server.mock("GET", "/api/v1/models/test_user/baseline/get")
+            .with_status(200)
+            .with_body(r##"{"id": 9, "ref": "test_user/baseline", "title": "Baseline", "author": "test_user",
+                           "subtitle": "", "isPrivate": true, "description": "# Old"}"##)
+            .create_async()
+            .await;
+        let update = server.mock("POST", "/api/v1/models/test_user/baseline/update")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "updateMask": "description",
+                "description": "# New",
+            })))
+            .with_status(200)
+            .with_body(r#"{"id": 9, "ref": "test_user/baseline"}"#)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        write_model_metadata(dir.path(), "# New");
+        client.update_model(dir.path()).await.unwrap();
+        update.assert_async().await;
+
+        write_model_metadata(dir.path(), "# Old");
+        let result = client.update_model(dir.path()).await;
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_model_reports_error() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _m = server.mock("POST", "/api/v1/models/test_user/baseline/delete")
+            .with_status(200)
+            .with_body(r#"{"error": "Model not found"}"#)
+            .create_async()
+            .await;
+
+        let result = client.delete_model(&"test_user/baseline".parse().unwrap()).await;
+        match result {
+            Err(Error::Other(msg)) => assert!(msg.contains("Model not found")),
+            _ => panic!("Expected delete error"),
+        }
+    }
+
+    fn write_model_instance_metadata(dir: &std::path::Path, usage: &str) {
+        std::fs::write(
+            dir.join("model-instance-metadata.json"),
+            serde_json::json!({
+                "ownerSlug": "test_user",
+                "modelSlug": "baseline",
+                "instanceSlug": "v1",
+                "framework": "pyTorch",
+                "overview": "",
+                "usage": usage,
+                "licenseName": "Apache 2.0",
+                "fineTunable": true,
+                "trainingData": []
+            })
+            .to_string(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_model_instance() {
+        let (client, mut server) = create_authenticated_client().await;
+        let m = server.mock("POST", "/api/v1/models/test_user/baseline/create/instance")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "instanceSlug": "v1",
+                "framework": "pyTorch",
+                "fineTunable": true,
+            })))
+            .with_status(200)
+            .with_body(r#"{"ref": "test_user/baseline/pyTorch/v1"}"#)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        write_model_instance_metadata(dir.path(), "");
+        let response = client.create_model_instance(dir.path()).await.unwrap();
+        m.assert_async().await;
+        assert_eq!(response.ref_.as_deref(), Some("test_user/baseline/pyTorch/v1"));
+    }
+
+    #[tokio::test]
+    async fn test_create_model_instance_rejects_dot_slugs() {
+        let (client, _server) = create_authenticated_client().await;
+        let dir = TempDir::new().unwrap();
+        write_model_instance_metadata(dir.path(), "");
+        let path = dir.path().join("model-instance-metadata.json");
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace(r#""modelSlug":"baseline""#, r#""modelSlug":"..""#)).unwrap();
+
+        let result = client.create_model_instance(dir.path()).await;
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_model_instance_sends_changed_fields_only() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _get = server.mock("GET", "/api/v1/models/test_user/baseline/pyTorch/v1/get")
+            .with_status(200)
+            .with_body(r#"{"id": 4, "slug": "v1", "framework": "pyTorch", "fineTunable": true,
+                          "licenseName": "Apache 2.0", "usage": "old", "overview": ""}"#)
+            .create_async()
+            .await;
+        let update = server.mock("POST", "/api/v1/models/test_user/baseline/pyTorch/v1/update")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "updateMask": "usage",
+                "usage": "new",
+            })))
+            .with_status(200)
+            .with_body(r#"{"ref": "test_user/baseline/pyTorch/v1"}"#)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        write_model_instance_metadata(dir.path(), "new");
+        client.update_model_instance(dir.path()).await.unwrap();
+        update.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete_model_instance_rejects_versioned_ref() {
+        let (client, _server) = create_authenticated_client().await;
+        let instance: ModelInstanceRef = "test_user/baseline/pyTorch/v1/2".parse().unwrap();
+
+        let result = client.delete_model_instance(&instance).await;
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_model_instance_version_uploads_tree() {
+        let (client, mut server) = create_authenticated_client().await;
+        let blobs = server.mock("POST", "/api/v1/blobs/upload")
+            .match_body(Matcher::PartialJson(serde_json::json!({"type": "model"})))
+            .with_status(200)
+            .with_body(format!(r#"{{"token": "tok", "createUrl": "{}/upload"}}"#, server.url()))
+            .expect(2)
+            .create_async()
+            .await;
+        let put = server.mock("PUT", "/upload")
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+        let create = server.mock("POST", "/api/v1/models/test_user/baseline/pyTorch/v1/create/version")
+            .match_body(Matcher::Json(serde_json::json!({
+                "versionNotes": "nightly",
+                "files": [{"token": "tok"}],
+                "directories": [{"name": "weights", "files": [{"token": "tok"}], "directories": []}],
+            })))
+            .with_status(200)
+            .with_body(r#"{"ref": "test_user/baseline/pyTorch/v1/2"}"#)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        write_model_instance_metadata(dir.path(), "");
+        std::fs::write(dir.path().join("config.json"), "{}").unwrap();
+        std::fs::write(dir.path().join(".DS_Store"), "").unwrap();
+        std::fs::create_dir(dir.path().join("weights")).unwrap();
+        std::fs::write(dir.path().join("weights/model.bin"), [0u8; 16]).unwrap();
+
+        client
+            .create_model_instance_version(&"test_user/baseline/pyTorch/v1".parse().unwrap(), dir.path(), "nightly")
+            .await
+            .unwrap();
+        blobs.assert_async().await;
+        put.assert_async().await;
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_model_instance_versions() {
+        let (client, mut server) = create_authenticated_client().await;
+        let _m = server.mock("GET", "/api/v1/models/test_user/baseline/pyTorch/v1/versions/list")
+            .match_query(Matcher::UrlEncoded("pageSize".into(), "20".into()))
+            .with_status(200)
+            .with_body(r#"{"versions": [{"versionNumber": 2, "versionNotes": "nightly",
+                          "createTime": "2024-05-01T00:00:00Z"}], "nextPageToken": ""}"#)
+            .create_async()
+            .await;
+
+        let response = client
+            .list_model_instance_versions(&"test_user/baseline/pyTorch/v1".parse().unwrap(), 20, None)
+            .await
+            .unwrap();
+        assert_eq!(response.versions[0].version_number, 2);
+        assert_eq!(response.versions[0].version_notes.as_deref(), Some("nightly"));
+    }
+
+    #[tokio::test]
+    async fn test_download_model_instance_version_extracts_archive() {
+        let (client, mut server) = create_authenticated_client().await;
+
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "weights/model.bin", &b"hello"[..])
+            .unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let _m = server.mock("GET", "/api/v1/models/test_user/baseline/pyTorch/v1/3/download")
+            .with_status(200)
+            .with_body(archive)
+            .create_async()
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        let path = client
+            .download_model_instance_version(&"test_user/baseline/pyTorch/v1/3".parse().unwrap(), dir.path(), true)
+            .await
+            .unwrap();
+
+        assert_eq!(path, dir.path());
+        assert_eq!(std::fs::read(dir.path().join("weights/model.bin")).unwrap(), b"hello");
+        assert!(!dir.path().join("v1-v3.tar.gz").exists());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_config_persists_next_to_credentials() {
+        let original_home = std::env::var("HOME").ok();
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("HOME", temp_dir.path());
+
+        let kaggle_dir = temp_dir.path().join(".kaggle");
+        std::fs::create_dir(&kaggle_dir).unwrap();
+        std::fs::write(
+            kaggle_dir.join("kaggle.json"),
+            r#"{"username": "test_user", "key": "test_key"}"#,
+        )
+        .unwrap();
+
+        let client = KaggleClient::new();
+        client.set_config("path", "~/data").await.unwrap();
+        client.set_config("competition", "titanic").await.unwrap();
+        assert!(client.set_config("proxy", "nope").await.is_err());
+
+        let reloaded = KaggleClient::new();
+        let config = reloaded.load_config().await.unwrap();
+        assert_eq!(config.path, Some(temp_dir.path().join("data")));
+        assert_eq!(config.competition.as_deref(), Some("titanic"));
+        assert_eq!(reloaded.download_path(None).await, temp_dir.path().join("data"));
+
+        reloaded.unset_config("competition").await.unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(kaggle_dir.join("kaggle.json")).unwrap())
+                .unwrap();
+        assert_eq!(json["username"], "test_user");
+        assert_eq!(json["key"], "test_key");
+        assert!(json.get("competition").is_none());
+        assert!(json.get("proxy").is_none());
+
+        match original_home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_config_set_keeps_higher_layers() {
+        let original_home = std::env::var("HOME").ok();
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("HOME", temp_dir.path());
+
+        let mut layers = ConfigLayers::default();
+        layers.add_command_line(["--competition".to_string(), "titanic".to_string()]).unwrap();
+        let client = KaggleClient::new();
+        client.load_config_layers(layers).await.unwrap();
+
+        client.set_config("competition", "spaceship-titanic").await.unwrap();
+        let resolved = client.config_sources().await;
+        assert_eq!(resolved.config.competition.as_deref(), Some("titanic"));
+        assert_eq!(resolved.source("competition").unwrap().layer, ConfigLayer::CommandLine);
+
+        // The saved value takes effect once nothing overrides it.
+        let reloaded = KaggleClient::new();
+        let config = reloaded.load_config().await.unwrap();
+        assert_eq!(config.competition.as_deref(), Some("spaceship-titanic"));
+        assert_eq!(
+            reloaded.config_sources().await.source("competition").unwrap().layer,
+            ConfigLayer::ConfigFile
+        );
+
+        match original_home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_credential_profiles() {
+        let orig_username = std::env::var("KAGGLE_USERNAME").ok();
+        let orig_key = std::env::var("KAGGLE_KEY").ok();
+        let original_home = std::env::var("HOME").ok();
+        std::env::remove_var("KAGGLE_USERNAME");
+        std::env::remove_var("KAGGLE_KEY");
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("HOME", temp_dir.path());
+
+        let kaggle_dir = temp_dir.path().join(".kaggle");
+        std::fs::create_dir(&kaggle_dir).unwrap();
+        std::fs::write(
+            kaggle_dir.join("kaggle.json"),
+            r#"{"username": "me", "key": "my_key", "profiles": {"bot": {"username": "team-bot", "key": "bot_key"}}}"#,
+        )
+        .unwrap();
+
+        let mut server = Server::new_async().await;
+        let m = server.mock("GET", Matcher::Regex("/api/v1/competitions/list".to_string()))
+            .match_header("authorization", Matcher::Regex("^Basic ".to_string()))
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        let client = KaggleClient::new().with_api_base(format!("{}/api/v1", server.url()));
+        client.load_credentials().await.unwrap();
+
+        let profiles = client.list_profiles().await.unwrap();
+        let names: Vec<_> = profiles.iter().map(|p| (p.name.as_str(), p.active)).collect();
+        assert_eq!(names, [("bot", false), ("default", true)]);
+
+        // A per-call profile does not switch the client's profile.
+        let bot = client.for_profile(Some("bot")).await.unwrap();
+        assert_eq!(bot.credentials.read().await.as_ref().unwrap().username(), Some("team-bot"));
+        assert_eq!(client.credentials.read().await.as_ref().unwrap().username(), Some("me"));
+        assert!(matches!(
+            client.for_profile(Some("missing")).await,
+            Err(Error::InvalidParameter(_))
+        ));
+
+        assert_eq!(client.use_profile("bot").await.unwrap().username.as_deref(), Some("team-bot"));
+        assert_eq!(client.credentials.read().await.as_ref().unwrap().secret(), "bot_key");
+        assert!(client.list_profiles().await.unwrap()[0].active);
+
+        let options = AuthenticateOptions {
+            profile: Some("personal".to_string()),
+            persist: true,
+            ..Default::default()
+        };
+        client
+            .authenticate_with("other".to_string(), "other_key".to_string(), &options)
+            .await
+            .unwrap();
+        m.assert_async().await;
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(kaggle_dir.join("kaggle.json")).unwrap())
+                .unwrap();
+        assert_eq!(json["username"], "me");
+        assert_eq!(json["profiles"]["bot"]["key"], "bot_key");
+        assert_eq!(json["profiles"]["personal"]["username"], "other");
+        let options = AuthenticateOptions {
+            profile: Some("bad name".to_string()),
+            ..Default::default()
+        };
+        assert!(client.authenticate_with(String::new(), String::new(), &options).await.is_err());
+
+        let secrets = client.known_secrets().await;
+        for key in ["my_key", "bot_key", "other_key"] {
+            assert!(secrets.contains(&key.to_string()));
+        }
+
+        std::env::set_var("KAGGLE_PROFILE", "bot");
+        let reloaded = KaggleClient::new();
+        reloaded.load_credentials().await.unwrap();
+        assert_eq!(reloaded.credentials.read().await.as_ref().unwrap().username(), Some("team-bot"));
+        std::env::remove_var("KAGGLE_PROFILE");
+
+        match original_home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        match orig_username {
+            Some(val) => std::env::set_var("KAGGLE_USERNAME", val),
+            None => std::env::remove_var("KAGGLE_USERNAME"),
+        }
+        match orig_key {
+            Some(val) => std::env::set_var("KAGGLE_KEY", val),
+            None => std::env::remove_var("KAGGLE_KEY"),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_authenticate_does_not_clobber_other_credentials() {
+        let original_home = std::env::var("HOME").ok();
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("HOME", temp_dir.path());
+        let kaggle_json = temp_dir.path().join(".kaggle").join("kaggle.json");
+
+        let mut server = Server::new_async().await;
+        let m = server.mock("GET", Matcher::Regex("/api/v1/competitions/list".to_string()))
+            .with_status(200)
+            .with_body("[]")
+            .expect(3)
+            .create_async()
+            .await;
+        let client = KaggleClient::new().with_api_base(format!("{}/api/v1", server.url()));
+
+        // Verifying and authenticating without persist leave no file behind.
+        let credentials: KaggleAuth = KaggleCredentials {
+            username: "me".to_string(),
+            key: "my_key".into(),
+        }
+        .into();
+        client.verify_credentials(&credentials).await.unwrap();
+        assert!(!client.is_authenticated().await);
+        client
+            .authenticate_with("me".to_string(), "my_key".to_string(), &AuthenticateOptions::default())
+            .await
+            .unwrap();
+        assert!(client.is_authenticated().await);
+        assert!(!kaggle_json.exists());
+
+        std::fs::create_dir_all(kaggle_json.parent().unwrap()).unwrap();
+        std::fs::write(&kaggle_json, r#"{"username": "teammate", "key": "their_key"}"#).unwrap();
+        let result = client.authenticate("me".to_string(), "my_key".to_string()).await;
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+        assert!(std::fs::read_to_string(&kaggle_json).unwrap().contains("their_key"));
+
+        let options = AuthenticateOptions {
+            persist: true,
+            force: true,
+            ..Default::default()
+        };
+        client
+            .authenticate_with("me".to_string(), "my_key".to_string(), &options)
+            .await
+            .unwrap();
+        assert!(std::fs::read_to_string(&kaggle_json).unwrap().contains("my_key"));
+        // The refused call never reached the API.
+        m.assert_async().await;
+
+        match original_home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_whoami_and_logout() {
+        let saved: Vec<_> = ["KAGGLE_USERNAME", "KAGGLE_KEY", "KAGGLE_API_TOKEN", "KAGGLE_PROFILE"]
+            .into_iter()
+            .map(|name| (name, std::env::var(name).ok()))
+            .collect();
+        for (name, _) in &saved {
+            std::env::remove_var(name);
+        }
+        let original_home = std::env::var("HOME").ok();
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("HOME", temp_dir.path());
+        let kaggle_json = temp_dir.path().join(".kaggle").join("kaggle.json");
+        std::fs::create_dir_all(kaggle_json.parent().unwrap()).unwrap();
+        std::fs::write(
+            &kaggle_json,
+            r#"{"username": "me", "key": "my_key", "competition": "titanic", "profiles": {"bot": {"username": "team-bot", "key": "bot_key"}}}"#,
+        )
+        .unwrap();
+
+        let mut server = Server::new_async().await;
+        let client = KaggleClient::new().with_api_base(format!("{}/api/v1", server.url()));
+        assert!(!client.whoami(false).await.unwrap().authenticated);
+
+        client.load_credentials().await.unwrap();
+        let identity = client.whoami(false).await.unwrap();
+        assert_eq!(identity.username.as_deref(), Some("me"));
+        assert_eq!(identity.scheme.as_deref(), Some("basic"));
+        assert_eq!(identity.source, Some(CredentialSource::ConfigFile));
+        assert_eq!(identity.profile.as_deref(), Some("default"));
+        assert!(!identity.verified);
+
+        // A successful request marks the credentials as verified.
+        let _m = server.mock("GET", Matcher::Regex("/api/v1/competitions/list".to_string()))
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        client
+            .list_competitions(
+                String::new(),
+                "all".to_string(),
+                "general".to_string(),
+                "latestDeadline".to_string(),
+                1,
+            )
+            .await
+            .unwrap();
+        assert!(client.whoami(false).await.unwrap().verified);
+
+        client.use_profile("bot").await.unwrap();
+        let identity = client.whoami(false).await.unwrap();
+        assert_eq!(identity.source, Some(CredentialSource::Profile));
+        assert!(!identity.verified);
+
+        assert_eq!(client.logout(true).await.unwrap().len(), 1);
+        assert!(!client.is_authenticated().await);
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&kaggle_json).unwrap()).unwrap();
+        assert!(json.get("profiles").is_none());
+        assert_eq!(json["username"], "me");
+
+        client.load_credentials().await.unwrap();
+        client.logout(true).await.unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&kaggle_json).unwrap()).unwrap();
+        assert!(json.get("key").is_none());
+        assert_eq!(json["competition"], "titanic");
+
+        // Logging out without deleting keeps the file as it is.
+        std::fs::write(&kaggle_json, r#"{"username": "me", "key": "my_key"}"#).unwrap();
+        client.load_credentials().await.unwrap();
+        assert!(client.logout(false).await.unwrap().is_empty());
+        assert!(kaggle_json.exists());
+        client.load_credentials().await.unwrap();
+        client.logout(true).await.unwrap();
+        assert!(!kaggle_json.exists());
+
+        for (name, value) in saved {
+            match value {
+                Some(value) => std::env::set_var(name, value),
+                None => std::env::remove_var(name),
+            }
+        }
+        match original_home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bearer_token_is_sent() {
+        let (client, mut server) = create_test_client().await;
+        let m = server.mock("GET", Matcher::Regex("/api/v1/competitions/list".to_string()))
+            .match_header("authorization", "Bearer test_token")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        {
+            let mut creds = client.credentials.write().await;
+            *creds = Some(AccessToken { token: "test_token".into(), username: None }.into());
+        }
+
+        client
+            .list_competitions(
+                String::new(),
+                "all".to_string(),
+                "general".to_string(),
+                "latestDeadline".to_string(),
+                1,
+            )
+            .await
+            .unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_load_access_token() {
+        let saved: Vec<_> = ["KAGGLE_USERNAME", "KAGGLE_KEY", "KAGGLE_API_TOKEN", "KAGGLE_PROFILE"]
+            .into_iter()
+            .map(|name| (name, std::env::var(name).ok()))
+            .collect();
+        for (name, _) in &saved {
+            std::env::remove_var(name);
+        }
+        let original_home = std::env::var("HOME").ok();
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("HOME", temp_dir.path());
+
+        let kaggle_dir = temp_dir.path().join(".kaggle");
+        std::fs::create_dir(&kaggle_dir).unwrap();
+        std::fs::write(kaggle_dir.join("kaggle.json"), r#"{"username": "me", "key": "my_key"}"#).unwrap();
+        std::fs::write(kaggle_dir.join("access_token"), "file_token\n").unwrap();
+
+        // The token file takes precedence over the legacy key.
+        let client = KaggleClient::new();
+        client.load_credentials().await.unwrap();
+        let creds = client.credentials.read().await.clone().unwrap();
+        assert_eq!((creds.scheme(), creds.secret()), ("bearer", "file_token"));
+
+        // So do environment variables, and a token over a key.
+        std::env::set_var("KAGGLE_USERNAME", "env_user");
+        std::env::set_var("KAGGLE_KEY", "env_key");
+        std::env::set_var("KAGGLE_API_TOKEN", "env_token");
+        client.load_credentials().await.unwrap();
+        let creds = client.credentials.read().await.clone().unwrap();
+        assert_eq!((creds.scheme(), creds.secret()), ("bearer", "env_token"));
+        assert_eq!(creds.username(), Some("env_user"));
+        assert!(client.known_secrets().await.contains(&"file_token".to_string()));
+
+        std::env::remove_var("KAGGLE_API_TOKEN");
+        client.load_credentials().await.unwrap();
+        assert_eq!(client.credentials.read().await.as_ref().unwrap().scheme(), "basic");
+
+        for (name, value) in saved {
+            match value {
+                Some(value) => std::env::set_var(name, value),
+                None => std::env::remove_var(name),
+            }
+        }
+        match original_home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configured_proxy_is_used() {
+        let mut proxy = Server::new_async().await;
+        let m = proxy.mock("GET", Matcher::Regex("/api/v1/competitions/list".to_string()))
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let client = KaggleClient::new()
+            .with_api_base("http://kaggle.invalid/api/v1")
+            .skip_save_credentials();
+        let config = KaggleConfig {
+            proxy: Some(proxy.url()),
+            ..Default::default()
+        };
+        client.apply_config(config).await.unwrap();
+        {
+            let mut creds = client.credentials.write().await;
+            *creds = Some(KaggleCredentials {
+                username: "test_user".to_string(),
+                key: "test_key".into(),
+            }.into());
+        }
+
+        client
+            .list_competitions(
+                String::new(),
+                "all".to_string(),
+                "general".to_string(),
+                "latestDeadline".to_string(),
+                1,
+            )
+            .await
+            .unwrap();
+        m.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_invalid_ca_certificate_file_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("ca.pem");
+        std::fs::write(&path, "not a certificate").unwrap();
+
+        let config = KaggleConfig {
+            ssl_ca_cert: Some(path.clone()),
+            ..Default::default()
+        };
+        assert!(matches!(build_http_client(&config), Err(Error::InvalidParameter(_))));
+
+        let mut config = KaggleConfig::default();
+        let result = config.set("ssl_ca_cert", dir.path().join("missing.pem").to_str().unwrap());
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_competition_uses_default() {
+        let (client, _server) = create_test_client().await;
+        let given: CompetitionRef = "titanic".parse().unwrap();
+
+        assert!(matches!(
+            client.resolve_competition(None).await,
+            Err(Error::InvalidParameter(_))
+        ));
+
+        let config = KaggleConfig {
+            competition: Some("llm-comp".to_string()),
+            ..Default::default()
+        };
+        client.apply_config(config).await.unwrap();
+        assert_eq!(client.resolve_competition(None).await.unwrap().slug, "llm-comp");
+        assert_eq!(client.resolve_competition(Some(&given)).await.unwrap(), given);
+    }
+
+    #[tokio::test]
+    async fn test_configured_api_base_is_used() {
+        let mut server = Server::new_async().await;
+        let m = server.mock("GET", Matcher::Regex("^/fake/competitions/list".to_string()))
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let client = KaggleClient::new().skip_save_credentials();
+        assert_eq!(client.api_base(), "https://www.kaggle.com/api/v1");
+        {
+            let mut creds = client.credentials.write().await;
+            *creds = Some(KaggleCredentials {
+                username: "test_user".to_string(),
+                key: "test_key".into(),
+            }.into());
+        }
+
+        let mut config = KaggleConfig::default();
+        config.set("api_base", &format!("{}/fake/", server.url())).unwrap();
+        client.apply_config(config).await.unwrap();
+        assert_eq!(client.api_base(), format!("{}/fake", server.url()));
+
+        client
+            .list_competitions(
+                String::new(),
+                "all".to_string(),
+                "general".to_string(),
+                "latestDeadline".to_string(),
+                1,
+            )
+            .await
+            .unwrap();
+        m.assert_async().await;
+
+        // The builder takes precedence over the configuration.
+        let client = client.with_api_base("http://localhost:1/api/v1");
+        assert_eq!(client.api_base(), "http://localhost:1/api/v1");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_config_dir_and_credential_file_permissions() {
+        let orig_username = std::env::var("KAGGLE_USERNAME").ok();
+        let orig_key = std::env::var("KAGGLE_KEY").ok();
+        let orig_token = std::env::var("KAGGLE_API_TOKEN").ok();
+        std::env::remove_var("KAGGLE_USERNAME");
+        std::env::remove_var("KAGGLE_KEY");
+        std::env::remove_var("KAGGLE_API_TOKEN");
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("KAGGLE_CONFIG_DIR", temp_dir.path());
+
+        let path = temp_dir.path().join("kaggle.json");
+        assert_eq!(kaggle_json_path(), Some(path.clone()));
+        std::fs::write(&path, r#"{"username": "me", "key": "my_key"}"#).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+            // World-readable files are only reported unless strict.
+            let client = KaggleClient::new();
+            client.load_credentials().await.unwrap();
+            assert_eq!(client.credentials.read().await.as_ref().unwrap().username(), Some("me"));
+
+            let strict = KaggleClient::new();
+            let config = KaggleConfig { strict_permissions: Some(true), ..Default::default() };
+            strict.apply_config(config).await.unwrap();
+            assert!(matches!(
+                strict.load_credentials().await,
+                Err(Error::InsecureCredentials(_))
+            ));
+            assert!(!strict.is_authenticated().await);
+
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+            strict.load_credentials().await.unwrap();
+            assert!(strict.is_authenticated().await);
+        }
+
+        std::env::remove_var("KAGGLE_CONFIG_DIR");
+        if let Some(val) = orig_username {
+            std::env::set_var("KAGGLE_USERNAME", val);
+        }
+        if let Some(val) = orig_key {
+            std::env::set_var("KAGGLE_KEY", val);
+        }
+        if let Some(val) = orig_token {
+            std::env::set_var("KAGGLE_API_TOKEN", val);
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_write_private_file_replaces_file_atomically() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("kaggle.json");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private_file(&path, "new").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_encrypted_credentials_store() {
+        let orig_username = std::env::var("KAGGLE_USERNAME").ok();
+        let orig_key = std::env::var("KAGGLE_KEY").ok();
+        std::env::remove_var("KAGGLE_USERNAME");
+        std::env::remove_var("KAGGLE_KEY");
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("KAGGLE_CONFIG_DIR", temp_dir.path());
+        std::env::set_var(PASSPHRASE_ENV_VAR, "correct horse");
+
+        let kaggle_json = temp_dir.path().join("kaggle.json");
+        std::fs::write(&kaggle_json, r#"{"username": "me", "key": "my_key", "competition": "titanic"}"#)
+            .unwrap();
+
+        let mut server = Server::new_async().await;
+        let m = server.mock("GET", Matcher::Regex("/api/v1/competitions/list".to_string()))
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        let client = KaggleClient::new().with_api_base(format!("{}/api/v1", server.url()));
+        let options = AuthenticateOptions {
+            profile: Some("bot".to_string()),
+            persist: true,
+            encrypt: true,
+            ..Default::default()
+        };
+        client
+            .authenticate_with("team-bot".to_string(), "bot_key".to_string(), &options)
+            .await
+            .unwrap();
+        m.assert_async().await;
+
+        // The plaintext file keeps only configuration values.
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&kaggle_json).unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!({"competition": "titanic"}));
+        let encrypted = std::fs::read_to_string(temp_dir.path().join(ENCRYPTED_CREDENTIALS_FILE)).unwrap();
+        assert!(!encrypted.contains("my_key") && !encrypted.contains("bot_key"));
+
+        let reloaded = KaggleClient::new();
+        reloaded.load_credentials().await.unwrap();
+        assert_eq!(reloaded.credentials.read().await.as_ref().unwrap().secret(), "my_key");
+        assert_eq!(reloaded.session.read().await.source, Some(CredentialSource::EncryptedFile));
+        let names: Vec<_> =
+            reloaded.list_profiles().await.unwrap().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["bot", "default"]);
+
+        std::env::set_var(PASSPHRASE_ENV_VAR, "wrong");
+        assert!(matches!(
+            KaggleClient::new().load_credentials().await,
+            Err(Error::AuthenticationError(_))
+        ));
+        assert!(credential_store::decrypt(&encrypted, "wrong").is_err());
+        assert!(credential_store::decrypt(&encrypted, "correct horse").is_ok());
+
+        std::env::remove_var(PASSPHRASE_ENV_VAR);
+        std::env::remove_var("KAGGLE_CONFIG_DIR");
+        if let Some(val) = orig_username {
+            std::env::set_var("KAGGLE_USERNAME", val);
+        }
+        if let Some(val) = orig_key {
+            std::env::set_var("KAGGLE_KEY", val);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_retries_transient_failures() {
+        let (client, mut server) = create_authenticated_client().await;
+        let client = client.with_retry_policy(RetryPolicy {
+            max_attempts: 3,
+            base_delay: std::time::Duration::from_millis(1),
+            max_delay: std::time::Duration::from_secs(5),
+        });
+
+        let unavailable = server.mock("GET", "/api/v1/competitions/list")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let ok = server.mock("GET", "/api/v1/competitions/list")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        let url = client.endpoint("/competitions/list");
+        client.request(client.http_client().get(&url)).await.unwrap();
+        unavailable.assert_async().await;
+        ok.assert_async().await;
+
+        // Submissions are not sent twice after a server error...
+        let submit = server.mock("POST", "/api/v1/competitions/submissions/submit/titanic")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let url = client.endpoint("/competitions/submissions/submit/titanic");
+        assert!(client.request(client.http_client().post(&url)).await.is_err());
+        submit.assert_async().await;
+
+        // ...but are after being rate limited, as the server told us when.
+        let limited = server.mock("POST", "/api/v1/competitions/submissions/url")
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(3)
+            .create_async()
+            .await;
+        let url = client.endpoint("/competitions/submissions/url");
+        match client.request(client.http_client().post(&url)).await {
+            Err(Error::ApiError(e)) => assert!(e.code.starts_with("429")),
+            other => panic!("Expected a 429 ApiError, got {:?}", other.map(|r| r.status())),
+        }
+        limited.assert_async().await;
+
+        // A Retry-After beyond the longest delay is not waited for.
+        let later = server.mock("GET", "/api/v1/datasets/list")
+            .with_status(429)
+            .with_header("Retry-After", "3600")
+            .expect(1)
+            .create_async()
+            .await;
+        let url = client.endpoint("/datasets/list");
+        assert!(client.request(client.http_client().get(&url)).await.is_err());
+        later.assert_async().await;
+    }
+
+    #[test]
+    fn test_token_bucket_spaces_out_bursts() {
+        use super::super::rate_limit::TokenBucket;
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        // 60 requests per minute allow a burst of 6, then one per second.
+        let mut bucket = TokenBucket::new(60, start);
+        for _ in 0..6 {
+            assert_eq!(bucket.take(start), Duration::ZERO);
+        }
+        assert_eq!(bucket.take(start), Duration::from_secs(1));
+        assert_eq!(bucket.take(start), Duration::from_secs(2));
+
+        // Waiting pays back what was borrowed.
+        let later = start + Duration::from_secs(3);
+        assert_eq!(bucket.take(later), Duration::ZERO);
+        assert_eq!(bucket.take(later + Duration::from_secs(60)), Duration::ZERO);
+
+        let mut unlimited = TokenBucket::new(0, start);
+        for _ in 0..100 {
+            assert_eq!(unlimited.take(start), Duration::ZERO);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_response_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("KAGGLE_CONFIG_DIR", temp_dir.path());
+        let (client, mut server) = create_authenticated_client().await;
+        let config = KaggleConfig { cache: Some(CacheMode::Disk), ..Default::default() };
+        client.apply_config(config.clone()).await.unwrap();
+        async fn get(client: &KaggleClient) -> String {
+            let url = client.endpoint("/competitions/list");
+            let response = client.request(client.http_client().get(&url)).await.unwrap();
+            response.text().await.unwrap()
+        }
+
+        let list = server.mock("GET", "/api/v1/competitions/list")
+            .with_status(200)
+            .with_header("ETag", "\"v1\"")
+            .with_body(r#"[{"ref": "titanic"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+        assert_eq!(get(&client).await, r#"[{"ref": "titanic"}]"#);
+        assert_eq!(get(&client).await, r#"[{"ref": "titanic"}]"#);
+        list.assert_async().await;
+
+        // Stale entries are revalidated with their ETag.
+        client.cache.expire().await;
+        let unchanged = server.mock("GET", "/api/v1/competitions/list")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create_async()
+            .await;
+        assert_eq!(get(&client).await, r#"[{"ref": "titanic"}]"#);
+        unchanged.assert_async().await;
+        unchanged.remove_async().await;
+
+        // An entry dropped while it is revalidated is fetched again in full.
+        client.cache.expire().await;
+        let cache = client.cache.clone();
+        let evicted = server.mock("GET", "/api/v1/competitions/list")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .with_body_from_request(move |_| {
+                cache.clear();
+                Vec::new()
+            })
+            .expect(1)
+            .create_async()
+            .await;
+        let refetch = server.mock("GET", "/api/v1/competitions/list")
+            .match_header("if-none-match", Matcher::Missing)
+            .with_status(200)
+            .with_header("ETag", "\"v1\"")
+            .with_body(r#"[{"ref": "titanic"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+        assert_eq!(get(&client).await, r#"[{"ref": "titanic"}]"#);
+        evicted.assert_async().await;
+        refetch.assert_async().await;
+        refetch.remove_async().await;
+
+        // Entries on disk survive a restart, but belong to one account.
+        let restarted = KaggleClient::new().with_api_base(client.api_base());
+        restarted.apply_config(config).await.unwrap();
+        *restarted.credentials.write().await = client.credentials.read().await.clone();
+        assert_eq!(get(&restarted).await, r#"[{"ref": "titanic"}]"#);
+        *restarted.credentials.write().await =
+            Some(AccessToken { token: "other_token".into(), username: None }.into());
+        let other = server.mock("GET", "/api/v1/competitions/list")
+            .with_status(200)
+            .with_body("[]")
+            .expect(2)
+            .create_async()
+            .await;
+        assert_eq!(get(&restarted).await, "[]");
+
+        // A submission drops the cached competition responses.
+        let submit = server.mock("POST", "/api/v1/competitions/submissions/submit/titanic")
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+        let submit_url = client.endpoint("/competitions/submissions/submit/titanic");
+        client.request(client.http_client().post(&submit_url)).await.unwrap();
+        submit.assert_async().await;
+        assert_eq!(get(&client).await, "[]");
+        other.assert_async().await;
+
+        // Kernel sources are cached in memory only.
+        let cache_dir = temp_dir.path().join("cache");
+        let files = std::fs::read_dir(&cache_dir).unwrap().count();
+        let _pull = server.mock("GET", "/api/v1/kernels/pull")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"metadata": {}, "blob": {"source": "secret"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let pull_url = client.endpoint("/kernels/pull?userName=me&kernelSlug=private");
+        for _ in 0..2 {
+            client.request(client.http_client().get(&pull_url)).await.unwrap();
+        }
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), files);
+
+        std::env::remove_var("KAGGLE_CONFIG_DIR");
+    }
+}
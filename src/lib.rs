@@ -38,6 +38,9 @@ pub mod client;
 /// Data models and types used throughout the crate
 pub mod models;
 
+/// Conversion between Jupyter notebooks and percent-format scripts
+pub mod notebook;
+
 /// MCP server implementation
 pub mod server;
 
@@ -5,14 +5,25 @@
 //! the Kaggle API.
 
 use anyhow::Result;
+use kaggle_mcp_rs::client::{self, KaggleClient};
+use kaggle_mcp_rs::models::{ConfigLayers, ALLOW_ENDPOINT_OVERRIDES_ENV_VAR};
 use kaggle_mcp_rs::server::KaggleMcpServer;
 use rmcp::{transport::stdio, ServiceExt};
 use tracing_subscriber::{self, EnvFilter};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Load .env file if it exists
-    dotenv::dotenv().ok();
+    // Collect configuration layers before .env is loaded into the
+    // environment, so the two sources can be told apart.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("encrypt-credentials") {
+        return encrypt_credentials().await;
+    }
+    let layers = ConfigLayers::discover(args)?;
+
+    // Load .env file if it exists, without variables that would redirect
+    // requests or credential files
+    let ignored_dotenv_vars = layers.export_dotenv().unwrap_or_default();
 
     // Initialize tracing to stderr to avoid interfering with stdio transport
     tracing_subscriber::fmt()
@@ -25,9 +36,37 @@ async fn main() -> Result<()> {
         .init();
 
     tracing::info!("Starting Kaggle MCP server");
+    for name in ignored_dotenv_vars {
+        tracing::warn!(
+            "Ignoring {} from .env; set it in the environment, or set {}=1",
+            name,
+            ALLOW_ENDPOINT_OVERRIDES_ENV_VAR
+        );
+    }
+
+    // The MCP transport owns stdin, so the passphrase is read from the
+    // terminal, if there is one.
+    if client::encrypted_credentials_path().is_some_and(|path| path.exists())
+        && std::env::var_os(client::PASSPHRASE_ENV_VAR).is_none()
+    {
+        match rpassword::prompt_password("Passphrase for the Kaggle credentials file: ") {
+            Ok(passphrase) => client::set_credentials_passphrase(passphrase.into()),
+            Err(e) => tracing::warn!(
+                "Cannot read the credentials passphrase ({}); set {}",
+                e,
+                client::PASSPHRASE_ENV_VAR
+            ),
+        }
+    }
+
+    // Resolve configuration (default competition, path, proxy) from every layer
+    let client = KaggleClient::new();
+    if let Err(e) = client.load_config_layers(layers).await {
+        tracing::warn!("Could not load configuration: {}", e);
+    }
 
     // Create and run the server
-    let service = KaggleMcpServer::new()
+    let service = KaggleMcpServer::with_client(client)
         .serve(stdio())
         .await
         .inspect_err(|e| {
@@ -36,4 +75,22 @@ async fn main() -> Result<()> {
 
     service.waiting().await?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Moves the credentials in `kaggle.json` into the encrypted credentials
+/// file (`kaggle-mcp-rs encrypt-credentials`).
+async fn encrypt_credentials() -> Result<()> {
+    if std::env::var_os(client::PASSPHRASE_ENV_VAR).is_none() {
+        let passphrase = rpassword::prompt_password("New passphrase: ")?;
+        if passphrase.is_empty() {
+            anyhow::bail!("The passphrase must not be empty");
+        }
+        if rpassword::prompt_password("Repeat passphrase: ")? != passphrase {
+            anyhow::bail!("The passphrases do not match");
+        }
+        client::set_credentials_passphrase(passphrase.into());
+    }
+    let path = client::encrypt_credentials().await?;
+    eprintln!("Credentials are now stored encrypted in {}", path.display());
+    Ok(())
+}
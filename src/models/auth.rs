@@ -1,17 +1,143 @@
 //! Authentication-related types.
 
+use super::Secret;
 use serde::{Deserialize, Serialize};
 
 /// Represents Kaggle API credentials.
 /// 
 /// This struct holds the username and API key required for authenticating
-/// with the Kaggle API.
+/// with the Kaggle API. The key is redacted when the struct is printed or
+/// serialized.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KaggleCredentials {
     /// Kaggle username
     pub username: String,
     /// Kaggle API key
-    pub key: String,
+    pub key: Secret,
+}
+
+/// An API access token, sent as a bearer token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessToken {
+    /// The token
+    pub token: Secret,
+    /// Kaggle username the token belongs to, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+/// Credentials requests are authenticated with.
+///
+/// The scheme follows from which credential is present: a username and
+/// legacy API key use HTTP basic auth, an access token is sent as a bearer
+/// token. Stored credentials are read as whichever shape matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KaggleAuth {
+    /// Username and legacy API key (HTTP basic auth)
+    Basic(KaggleCredentials),
+    /// API access token (`Authorization: Bearer`)
+    Bearer(AccessToken),
+}
+
+impl KaggleAuth {
+    /// Returns the name of the authentication scheme (`basic` or `bearer`).
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            Self::Basic(_) => "basic",
+            Self::Bearer(_) => "bearer",
+        }
+    }
+
+    /// Returns the Kaggle username, if known.
+    pub fn username(&self) -> Option<&str> {
+        match self {
+            Self::Basic(credentials) => Some(&credentials.username),
+            Self::Bearer(token) => token.username.as_deref(),
+        }
+    }
+
+    /// Returns the API key or token.
+    pub fn secret(&self) -> &str {
+        match self {
+            Self::Basic(credentials) => credentials.key.expose(),
+            Self::Bearer(token) => token.token.expose(),
+        }
+    }
+
+    /// Adds the authorization header for this scheme to a request.
+    pub fn apply(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self {
+            Self::Basic(credentials) => {
+                builder.basic_auth(&credentials.username, Some(credentials.key.expose()))
+            }
+            Self::Bearer(token) => builder.bearer_auth(token.token.expose()),
+        }
+    }
+}
+
+impl From<KaggleCredentials> for KaggleAuth {
+    fn from(credentials: KaggleCredentials) -> Self {
+        Self::Basic(credentials)
+    }
+}
+
+impl From<AccessToken> for KaggleAuth {
+    fn from(token: AccessToken) -> Self {
+        Self::Bearer(token)
+    }
+}
+
+/// Where the active credentials came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialSource {
+    /// `KAGGLE_API_TOKEN`, or `KAGGLE_USERNAME` and `KAGGLE_KEY`
+    Environment,
+    /// `~/.kaggle/access_token`
+    AccessTokenFile,
+    /// The default credentials in `~/.kaggle/kaggle.json`
+    ConfigFile,
+    /// The default credentials in the encrypted credentials file
+    EncryptedFile,
+    /// A named profile in `~/.kaggle/kaggle.json`
+    Profile,
+    /// The `authenticate` tool
+    ToolCall,
+}
+
+/// The account requests are made with, as reported by `whoami`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Identity {
+    /// Whether any credentials are loaded
+    pub authenticated: bool,
+    /// Kaggle username, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Authentication scheme (`basic` or `bearer`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+    /// Where the credentials came from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<CredentialSource>,
+    /// Profile the credentials belong to, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Whether the Kaggle API has accepted the credentials
+    pub verified: bool,
+}
+
+/// Options for [`authenticate_with`](crate::client::KaggleClient::authenticate_with).
+#[derive(Debug, Clone, Default)]
+pub struct AuthenticateOptions {
+    /// Profile to save the credentials under; `None` for the default profile
+    pub profile: Option<String>,
+    /// Save the credentials to `kaggle.json`
+    pub persist: bool,
+    /// Overwrite saved credentials that belong to a different username
+    pub force: bool,
+    /// Save to the encrypted credentials file, creating it if needed
+    pub encrypt: bool,
 }
 
 /// Request parameters for authentication.
@@ -34,4 +160,20 @@ pub struct AuthenticationResponse {
     pub message: String,
     /// The authenticated username (present on success)
     pub username: Option<String>,
-}
\ No newline at end of file
+}
+
+/// A named set of credentials in the credentials store.
+///
+/// The key is never included, so profiles can be listed safely.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialProfile {
+    /// Profile name; `default` is the top-level `username`/`key` pair
+    pub name: String,
+    /// Kaggle username of the profile, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Authentication scheme (`basic` or `bearer`)
+    pub scheme: String,
+    /// Whether the profile is the one requests are currently made with
+    pub active: bool,
+}
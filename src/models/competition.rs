@@ -42,4 +42,36 @@ pub struct CompetitionListRequest {
     pub sort_by: Option<String>,
     /// Page number for pagination
     pub page: Option<i32>,
-}
\ No newline at end of file
+}
+
+/// Request body for submitting a kernel version to a code competition.
+///
+/// Mirrors `ApiCreateCodeSubmissionRequest` of the official `kagglesdk`
+/// (what `kaggle competitions submit -k` sends), posted to
+/// `/competitions/submissions/submit-notebook/{competitionName}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeSubmissionRequest {
+    /// Competition slug
+    pub competition_name: String,
+    /// Username of the kernel owner
+    pub kernel_owner: String,
+    /// Kernel slug
+    pub kernel_slug: String,
+    /// Kernel version to submit
+    pub kernel_version: i32,
+    /// Name of the output file to submit (e.g., "submission.csv")
+    pub file_name: String,
+    /// Submission description
+    pub submission_description: String,
+}
+
+/// Response returned after creating a submission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionResponse {
+    /// Human-readable result message
+    pub message: String,
+    /// Submission reference, if one was created
+    #[serde(rename = "ref", default)]
+    pub ref_: Option<i64>,
+}
@@ -1,28 +1,709 @@
 //! Configuration types for the Kaggle API client.
+//!
+//! Settings are resolved from several layers. From lowest to highest
+//! precedence:
+//!
+//! 1. Built-in defaults
+//! 2. The Kaggle config file (`~/.kaggle/kaggle.json`, or `kaggle.json` in
+//!    `KAGGLE_CONFIG_DIR`)
+//! 3. A project-local `.kaggle-mcp.toml`, found in the working directory or
+//!    one of its parents
+//! 4. A `.env` file, found the same way
+//! 5. `KAGGLE_COMPETITION`, `KAGGLE_PATH`, `KAGGLE_PROXY`,
+//!    `KAGGLE_SSL_CA_CERT`, `KAGGLE_API_BASE`, `KAGGLE_STRICT_PERMISSIONS`,
+//!    `KAGGLE_MAX_ATTEMPTS`, `KAGGLE_REQUESTS_PER_MINUTE` and `KAGGLE_CACHE`
+//!    environment variables
+//! 6. Command line flags (`--competition`, `--path`, `--proxy`,
+//!    `--ssl-ca-cert`, `--api-base`, `--strict-permissions`, `--max-attempts`,
+//!    `--requests-per-minute`, `--cache`)
+//!
+//! `proxy` and `api_base` decide where credentials are sent, so they are
+//! ignored in a project file or `.env` (which may come with a cloned
+//! repository) unless `KAGGLE_MCP_ALLOW_ENDPOINT_OVERRIDES` is set.
 
+use super::secret::redact_url;
+use super::{CompetitionRef, Error};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Name of the project-local configuration file.
+pub const PROJECT_CONFIG_FILE: &str = ".kaggle-mcp.toml";
+
+/// Environment variable that allows `proxy` and `api_base` to be set by a
+/// project file, a `.env` file or the `config_set` tool.
+pub const ALLOW_ENDPOINT_OVERRIDES_ENV_VAR: &str = "KAGGLE_MCP_ALLOW_ENDPOINT_OVERRIDES";
+
+/// Environment variables that redirect requests or credential files, which
+/// a `.env` file may only set when endpoint overrides are allowed.
+const PROTECTED_ENV_VARS: [&str; 8] = [
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "NO_PROXY",
+    "KAGGLE_CONFIG_DIR",
+    "KAGGLE_PROXY",
+    "KAGGLE_API_BASE",
+    ALLOW_ENDPOINT_OVERRIDES_ENV_VAR,
+];
+
+/// Returns whether `KAGGLE_MCP_ALLOW_ENDPOINT_OVERRIDES` is set to a true value.
+fn endpoint_overrides_allowed() -> bool {
+    std::env::var(ALLOW_ENDPOINT_OVERRIDES_ENV_VAR)
+        .ok()
+        .and_then(|value| parse_bool(ALLOW_ENDPOINT_OVERRIDES_ENV_VAR, &value).ok())
+        .unwrap_or(false)
+}
 
 /// Configuration settings for the Kaggle API client.
 /// 
 /// This struct holds various configuration options that affect how
-/// the client interacts with the Kaggle API.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// the client interacts with the Kaggle API. The values are stored in
+/// `kaggle.json` next to the credentials, as the official Kaggle CLI does.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct KaggleConfig {
     /// Default competition to use for operations
     pub competition: Option<String>,
     /// Default download path for files
     pub path: Option<PathBuf>,
     /// HTTP proxy URL to use for API requests
+    #[serde(serialize_with = "serialize_redacted_url")]
     pub proxy: Option<String>,
+    /// PEM file with extra CA certificates to trust (e.g., of a
+    /// TLS-inspecting proxy)
+    pub ssl_ca_cert: Option<PathBuf>,
+    /// Base URL of the Kaggle API, e.g. of a local fake or recording proxy
+    #[serde(serialize_with = "serialize_redacted_url")]
+    pub api_base: Option<String>,
+    /// Refuse to load credential files that other users can read or that
+    /// belong to another user, instead of only warning
+    pub strict_permissions: Option<bool>,
+    /// Attempts per API request, including retries of transient failures
+    pub max_attempts: Option<u32>,
+    /// Client-side limit on API requests per minute; 0 for no limit
+    pub requests_per_minute: Option<u32>,
+    /// Where responses of read-only endpoints are cached
+    pub cache: Option<CacheMode>,
+}
+
+/// Where responses of read-only API endpoints are cached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheMode {
+    /// Responses are not cached
+    Off,
+    /// Responses are cached in memory until the server stops
+    #[default]
+    Memory,
+    /// Responses are also cached on disk, surviving restarts
+    Disk,
+}
+
+impl std::str::FromStr for CacheMode {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "memory" => Ok(Self::Memory),
+            "disk" => Ok(Self::Disk),
+            _ => Err(Error::InvalidParameter(format!(
+                "cache must be off, memory or disk, got '{}'",
+                value
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for CacheMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Off => "off",
+            Self::Memory => "memory",
+            Self::Disk => "disk",
+        })
+    }
+}
+
+impl KaggleConfig {
+    /// Names of the configuration keys.
+    pub const KEYS: [&'static str; 9] = [
+        "competition",
+        "path",
+        "proxy",
+        "ssl_ca_cert",
+        "api_base",
+        "strict_permissions",
+        "max_attempts",
+        "requests_per_minute",
+        "cache",
+    ];
+
+    /// Keys that decide where requests, and so credentials, are sent.
+    pub const ENDPOINT_KEYS: [&'static str; 2] = ["proxy", "api_base"];
+
+    /// Validates and sets a configuration value.
+    /// 
+    /// * `competition` accepts a competition slug or URL and stores the slug
+    /// * `path` must be absolute or start with `~`, and must not be a file
+    /// * `proxy` must be an `http`, `https`, `socks5` or `socks5h` URL
+    /// * `ssl_ca_cert` must be a readable PEM file with at least one certificate
+    /// * `api_base` must be an `http` or `https` URL; a trailing `/` is removed
+    /// * `strict_permissions` must be `true` or `false`
+    /// * `max_attempts` must be a whole number from 1 to 10
+    /// * `requests_per_minute` must be a whole number; 0 disables the limit
+    /// * `cache` must be `off`, `memory` or `disk`
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        let value = value.trim();
+        match key {
+            "competition" => {
+                let competition: CompetitionRef = value.parse()?;
+                self.competition = Some(competition.slug);
+            }
+            "path" => self.path = Some(validate_path(value)?),
+            "proxy" => self.proxy = Some(validate_proxy(value)?),
+            "ssl_ca_cert" => {
+                let path = expand_home(value)?;
+                load_ca_certificates(&path)?;
+                self.ssl_ca_cert = Some(path);
+            }
+            "api_base" => self.api_base = Some(validate_api_base(value)?),
+            "strict_permissions" => self.strict_permissions = Some(parse_bool(key, value)?),
+            "max_attempts" => self.max_attempts = Some(validate_max_attempts(value)?),
+            "requests_per_minute" => {
+                self.requests_per_minute = Some(value.parse().map_err(|_| {
+                    Error::InvalidParameter(format!(
+                        "requests_per_minute must be a whole number, got '{}'",
+                        value
+                    ))
+                })?)
+            }
+            "cache" => self.cache = Some(value.parse()?),
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    /// Returns a configuration value as a string, or `None` if it is unset.
+    pub fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "competition" => self.competition.clone(),
+            "path" => self.path.as_ref().map(|p| p.display().to_string()),
+            "proxy" => self.proxy.clone(),
+            "ssl_ca_cert" => self.ssl_ca_cert.as_ref().map(|p| p.display().to_string()),
+            "api_base" => self.api_base.clone(),
+            "strict_permissions" => self.strict_permissions.map(|b| b.to_string()),
+            "max_attempts" => self.max_attempts.map(|n| n.to_string()),
+            "requests_per_minute" => self.requests_per_minute.map(|n| n.to_string()),
+            "cache" => self.cache.map(|mode| mode.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Clears a configuration value.
+    pub fn unset(&mut self, key: &str) -> Result<(), Error> {
+        match key {
+            "competition" => self.competition = None,
+            "path" => self.path = None,
+            "proxy" => self.proxy = None,
+            "ssl_ca_cert" => self.ssl_ca_cert = None,
+            "api_base" => self.api_base = None,
+            "strict_permissions" => self.strict_permissions = None,
+            "max_attempts" => self.max_attempts = None,
+            "requests_per_minute" => self.requests_per_minute = None,
+            "cache" => self.cache = None,
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for KaggleConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Proxy and API URLs may carry a password.
+        f.debug_struct("KaggleConfig")
+            .field("competition", &self.competition)
+            .field("path", &self.path)
+            .field("proxy", &self.proxy.as_deref().map(redact_url))
+            .field("ssl_ca_cert", &self.ssl_ca_cert)
+            .field("api_base", &self.api_base.as_deref().map(redact_url))
+            .field("strict_permissions", &self.strict_permissions)
+            .field("max_attempts", &self.max_attempts)
+            .field("requests_per_minute", &self.requests_per_minute)
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
+
+/// Serializes a proxy or API URL without its password.
+fn serialize_redacted_url<S: serde::Serializer>(url: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    url.as_deref().map(redact_url).serialize(serializer)
+}
+
+/// Serializes resolved values with the passwords in proxy and API URLs
+/// removed.
+fn serialize_redacted_values<S: serde::Serializer>(
+    values: &BTreeMap<String, ResolvedValue>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let redacted: BTreeMap<&String, ResolvedValue> = values
+        .iter()
+        .map(|(key, value)| {
+            let mut value = value.clone();
+            if KaggleConfig::ENDPOINT_KEYS.contains(&key.as_str()) {
+                value.value = value.value.as_deref().map(redact_url);
+            }
+            (key, value)
+        })
+        .collect();
+    redacted.serialize(serializer)
+}
+
+/// Expands a leading `~` and checks that the path is absolute.
+fn expand_home(value: &str) -> Result<PathBuf, Error> {
+    let path = match value.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = directories::UserDirs::new()
+                .ok_or_else(|| Error::Other("Could not determine home directory".to_string()))?
+                .home_dir()
+                .to_path_buf();
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(value),
+    };
+
+    if !path.is_absolute() {
+        return Err(Error::InvalidParameter(format!(
+            "path '{}' must be absolute or start with ~",
+            value
+        )));
+    }
+    Ok(path)
+}
+
+/// Expands a leading `~` and checks that the path can be a directory.
+fn validate_path(value: &str) -> Result<PathBuf, Error> {
+    let path = expand_home(value)?;
+    if path.exists() && !path.is_dir() {
+        return Err(Error::InvalidParameter(format!(
+            "path '{}' exists and is not a directory",
+            value
+        )));
+    }
+    Ok(path)
+}
+
+/// Checks that a proxy URL is well-formed and uses a supported scheme.
+fn validate_proxy(value: &str) -> Result<String, Error> {
+    let url = reqwest::Url::parse(value)
+        .map_err(|e| Error::InvalidParameter(format!("proxy is not a valid URL: {}", e)))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") || url.host_str().is_none() {
+        return Err(Error::InvalidParameter(format!(
+            "proxy '{}' must be an http, https, socks5 or socks5h URL with a host",
+            redact_url(value)
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// Checks that an API base URL is an `http` or `https` URL with a host.
+fn validate_api_base(value: &str) -> Result<String, Error> {
+    let url = reqwest::Url::parse(value)
+        .map_err(|e| Error::InvalidParameter(format!("api_base is not a valid URL: {}", e)))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(Error::InvalidParameter(format!(
+            "api_base '{}' must be an http or https URL with a host",
+            redact_url(value)
+        )));
+    }
+    Ok(value.trim_end_matches('/').to_string())
+}
+
+/// Parses a boolean setting such as `true`, `false`, `1` or `0`.
+fn parse_bool(key: &str, value: &str) -> Result<bool, Error> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(Error::InvalidParameter(format!(
+            "{} must be true or false, got '{}'",
+            key, value
+        ))),
+    }
+}
+
+/// Checks that a number of request attempts is from 1 to 10.
+fn validate_max_attempts(value: &str) -> Result<u32, Error> {
+    match value.parse::<u32>() {
+        Ok(attempts @ 1..=10) => Ok(attempts),
+        _ => Err(Error::InvalidParameter(format!(
+            "max_attempts must be a whole number from 1 to 10, got '{}'",
+            value
+        ))),
+    }
+}
+
+/// Reads the certificates in a PEM file.
+pub(crate) fn load_ca_certificates(path: &Path) -> Result<Vec<reqwest::Certificate>, Error> {
+    let pem = std::fs::read(path).map_err(|e| {
+        Error::InvalidParameter(format!("Cannot read CA certificate file {:?}: {}", path, e))
+    })?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+        Error::InvalidParameter(format!("Invalid CA certificate file {:?}: {}", path, e))
+    })?;
+    if certificates.is_empty() {
+        return Err(Error::InvalidParameter(format!(
+            "CA certificate file {:?} contains no certificates",
+            path
+        )));
+    }
+    Ok(certificates)
+}
+
+/// Builds the error for an unsupported configuration key.
+fn unknown_key(key: &str) -> Error {
+    Error::InvalidParameter(format!(
+        "Unknown configuration key '{}' (expected one of: {})",
+        key,
+        KaggleConfig::KEYS.join(", ")
+    ))
+}
+
+/// A configuration layer, in increasing order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigLayer {
+    /// Built-in default
+    Default,
+    /// `~/.kaggle/kaggle.json`
+    ConfigFile,
+    /// Project-local `.kaggle-mcp.toml`
+    ProjectFile,
+    /// `.env` file
+    DotEnv,
+    /// Environment variable
+    Environment,
+    /// Command line flag
+    CommandLine,
+}
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigSource {
+    /// The layer that provided the value
+    pub layer: ConfigLayer,
+    /// The file, variable or flag within the layer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
 }
 
-impl Default for KaggleConfig {
-    fn default() -> Self {
+impl ConfigSource {
+    /// Creates a source for a layer and location.
+    pub fn new(layer: ConfigLayer, location: impl Into<String>) -> Self {
         Self {
-            competition: None,
-            path: None,
-            proxy: None,
+            layer,
+            location: Some(location.into()),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let layer = match self.layer {
+            ConfigLayer::Default => "default",
+            ConfigLayer::ConfigFile => "config file",
+            ConfigLayer::ProjectFile => "project file",
+            ConfigLayer::DotEnv => ".env file",
+            ConfigLayer::Environment => "environment variable",
+            ConfigLayer::CommandLine => "command line flag",
+        };
+        match self.location {
+            Some(ref location) => write!(f, "{} {}", layer, location),
+            None => f.write_str(layer),
+        }
+    }
+}
+
+/// An effective configuration value and where it came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedValue {
+    /// The effective value, or `None` if unset
+    pub value: Option<String>,
+    /// The layer the value came from
+    pub source: ConfigSource,
+}
+
+/// The effective configuration and the provenance of each value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedConfig {
+    /// The effective configuration
+    #[serde(skip)]
+    pub config: KaggleConfig,
+    /// Each key's effective value and source
+    #[serde(serialize_with = "serialize_redacted_values")]
+    pub values: BTreeMap<String, ResolvedValue>,
+    /// Values that were ignored because they failed validation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl ResolvedConfig {
+    /// Returns the source of a key's effective value.
+    pub fn source(&self, key: &str) -> Option<&ConfigSource> {
+        self.values.get(key).map(|value| &value.source)
+    }
+}
+
+/// Raw configuration values from every layer.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    values: Vec<(ConfigSource, String, String)>,
+    allow_endpoint_overrides: bool,
+}
+
+impl ConfigLayers {
+    /// Collects the project file, `.env`, environment and command line layers.
+    ///
+    /// The environment is read as it is when this is called, so call it
+    /// before loading `.env` into the environment to keep the two apart
+    /// (and so `.env` cannot set `KAGGLE_MCP_ALLOW_ENDPOINT_OVERRIDES`).
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - Command line arguments, without the program name
+    ///
+    /// # Returns
+    ///
+    /// Returns the layers, or `Error::InvalidParameter` for an unknown flag,
+    /// a flag without a value or an unreadable project file.
+    pub fn discover<I>(args: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut layers = Self::default();
+        layers.allow_endpoint_overrides(endpoint_overrides_allowed());
+        let current_dir = std::env::current_dir()?;
+
+        if let Some(path) = find_upwards(&current_dir, PROJECT_CONFIG_FILE) {
+            layers.add_project_file(&path)?;
+        }
+
+        if let Some(path) = find_upwards(&current_dir, ".env") {
+            let location = path.display().to_string();
+            // Read the file without touching the process environment.
+            #[allow(deprecated)]
+            let entries = dotenv::from_path_iter(&path)
+                .map_err(|e| Error::Other(format!("Cannot read {:?}: {}", path, e)))?;
+            for (name, value) in entries.flatten() {
+                if let Some(key) = key_for_env_var(&name) {
+                    layers.push(ConfigSource::new(ConfigLayer::DotEnv, location.clone()), key, value);
+                }
+            }
+        }
+
+        layers.add_environment(std::env::vars().collect());
+        layers.add_command_line(args)?;
+        Ok(layers)
+    }
+
+    /// Loads the nearest `.env` into the process environment, like
+    /// `dotenv::dotenv()`, except for proxy variables and
+    /// `KAGGLE_CONFIG_DIR` unless endpoint overrides are allowed. Variables
+    /// that are already set are kept.
+    ///
+    /// # Returns
+    ///
+    /// Returns the names of the variables that were left out.
+    pub fn export_dotenv(&self) -> Result<Vec<String>, Error> {
+        match find_upwards(&std::env::current_dir()?, ".env") {
+            Some(path) => export_dotenv_file(&path, self.allow_endpoint_overrides),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Adds the values of a `.kaggle-mcp.toml` file.
+    ///
+    /// Relative `path` and `ssl_ca_cert` values are resolved against the
+    /// file's directory.
+    pub fn add_project_file(&mut self, path: &Path) -> Result<(), Error> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::InvalidParameter(format!("Cannot read {:?}: {}", path, e)))?;
+        let table: toml::Table = content
+            .parse()
+            .map_err(|e| Error::InvalidParameter(format!("Invalid {:?}: {}", path, e)))?;
+        let directory = path.parent().unwrap_or(Path::new("."));
+
+        for (key, value) in table {
+            let mut value = match value {
+                toml::Value::String(value) => value,
+                other => other.to_string(),
+            };
+            let is_relative_path = matches!(key.as_str(), "path" | "ssl_ca_cert")
+                && !value.starts_with('~')
+                && Path::new(&value).is_relative();
+            if is_relative_path {
+                value = directory.join(&value).display().to_string();
+            }
+            self.push(
+                ConfigSource::new(ConfigLayer::ProjectFile, path.display().to_string()),
+                key,
+                value,
+            );
+        }
+        Ok(())
+    }
+
+    /// Adds `KAGGLE_*` configuration variables from an environment.
+    pub fn add_environment(&mut self, environment: HashMap<String, String>) {
+        let mut names: Vec<_> = environment.keys().collect();
+        names.sort();
+        for name in names {
+            if let Some(key) = key_for_env_var(name) {
+                self.push(
+                    ConfigSource::new(ConfigLayer::Environment, name.clone()),
+                    key,
+                    environment[name].clone(),
+                );
+            }
+        }
+    }
+
+    /// Adds command line flags such as `--path /data` or `--path=/data`.
+    pub fn add_command_line<I>(&mut self, args: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                return Err(Error::InvalidParameter(format!("Unexpected argument '{}'", arg)));
+            };
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => {
+                    let value = args.next().ok_or_else(|| {
+                        Error::InvalidParameter(format!("Flag --{} requires a value", flag))
+                    })?;
+                    (flag.to_string(), value)
+                }
+            };
+            let key = name.replace('-', "_");
+            if !KaggleConfig::KEYS.contains(&key.as_str()) {
+                return Err(Error::InvalidParameter(format!(
+                    "Unknown flag --{} (expected one of: {})",
+                    name,
+                    KaggleConfig::KEYS.map(|k| format!("--{}", k.replace('_', "-"))).join(", ")
+                )));
+            }
+            self.push(ConfigSource::new(ConfigLayer::CommandLine, format!("--{}", name)), key, value);
+        }
+        Ok(())
+    }
+
+    /// Allows `proxy` and `api_base` from the project file and `.env`
+    /// layers, which are otherwise ignored.
+    pub fn allow_endpoint_overrides(&mut self, allow: bool) {
+        self.allow_endpoint_overrides = allow;
+    }
+
+    /// Returns whether `proxy` and `api_base` may come from the project file
+    /// and `.env` layers, and be set through the `config_set` tool.
+    pub fn allows_endpoint_overrides(&self) -> bool {
+        self.allow_endpoint_overrides
+    }
+
+    /// Adds a value to a layer.
+    pub fn push(&mut self, source: ConfigSource, key: impl Into<String>, value: impl Into<String>) {
+        self.values.push((source, key.into(), value.into()));
+    }
+
+    /// Replaces or removes a key's value in one layer.
+    pub fn replace(&mut self, source: ConfigSource, key: &str, value: Option<String>) {
+        self.values
+            .retain(|(existing, existing_key, _)| existing.layer != source.layer || existing_key != key);
+        if let Some(value) = value {
+            self.push(source, key, value);
+        }
+    }
+
+    /// Resolves the effective configuration.
+    ///
+    /// Higher layers override lower ones. Values that fail validation are
+    /// skipped and reported in `warnings`, so a bad value in one layer does
+    /// not hide a good one below it. So are `proxy` and `api_base` from the
+    /// project file and `.env` layers, unless overrides are allowed.
+    pub fn resolve(&self) -> ResolvedConfig {
+        let mut resolved = ResolvedConfig::default();
+        for key in KaggleConfig::KEYS {
+            resolved.values.insert(
+                key.to_string(),
+                ResolvedValue {
+                    value: None,
+                    source: ConfigSource {
+                        layer: ConfigLayer::Default,
+                        location: None,
+                    },
+                },
+            );
         }
+
+        let mut values: Vec<_> = self.values.iter().collect();
+        values.sort_by_key(|(source, _, _)| source.layer);
+        for (source, key, value) in values {
+            let untrusted = matches!(source.layer, ConfigLayer::ProjectFile | ConfigLayer::DotEnv);
+            if untrusted && !self.allow_endpoint_overrides && KaggleConfig::ENDPOINT_KEYS.contains(&key.as_str()) {
+                resolved.warnings.push(format!(
+                    "Ignoring {} from {}: it would decide where credentials are sent; \
+                     set it in the environment or on the command line, or set {}=1",
+                    key, source, ALLOW_ENDPOINT_OVERRIDES_ENV_VAR
+                ));
+                continue;
+            }
+            match resolved.config.set(key, value) {
+                Ok(()) => {
+                    resolved.values.insert(
+                        key.clone(),
+                        ResolvedValue {
+                            value: resolved.config.get(key),
+                            source: source.clone(),
+                        },
+                    );
+                }
+                Err(e) => resolved.warnings.push(format!("Ignoring {} from {}: {}", key, source, e)),
+            }
+        }
+        resolved
     }
-}
\ No newline at end of file
+}
+
+/// Loads a `.env` file into the process environment; see
+/// [`ConfigLayers::export_dotenv`].
+pub fn export_dotenv_file(path: &Path, allow_endpoint_overrides: bool) -> Result<Vec<String>, Error> {
+    #[allow(deprecated)]
+    let entries = dotenv::from_path_iter(path)
+        .map_err(|e| Error::Other(format!("Cannot read {:?}: {}", path, e)))?;
+    let mut ignored = Vec::new();
+    for (name, value) in entries.flatten() {
+        let protected = PROTECTED_ENV_VARS.iter().any(|var| var.eq_ignore_ascii_case(&name));
+        if protected && !allow_endpoint_overrides {
+            ignored.push(name);
+        } else if std::env::var_os(&name).is_none() {
+            std::env::set_var(name, value);
+        }
+    }
+    Ok(ignored)
+}
+
+/// Maps an environment variable such as `KAGGLE_PATH` to its configuration key.
+fn key_for_env_var(name: &str) -> Option<&'static str> {
+    let key = name.strip_prefix("KAGGLE_")?.to_ascii_lowercase();
+    KaggleConfig::KEYS.into_iter().find(|k| *k == key)
+}
+
+/// Finds a file in a directory or the nearest of its parents.
+fn find_upwards(start: &Path, file_name: &str) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(file_name))
+        .find(|path| path.is_file())
+}
@@ -50,6 +50,18 @@ pub enum Error {
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
     
+    /// Notebook could not be parsed or converted
+    #[error("Notebook error: {0}")]
+    NotebookError(String),
+    
+    /// Potential secrets were found in content about to be published
+    #[error("Potential secrets detected: {0}")]
+    SecretsDetected(String),
+    
+    /// A credential file can be read by other users or belongs to another user
+    #[error("Insecure credentials file: {0}")]
+    InsecureCredentials(String),
+    
     /// No authentication credentials available
     #[error("Not authenticated")]
     NotAuthenticated,
@@ -1,6 +1,8 @@
 //! Kernel (notebook) related types.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Represents a Kaggle kernel (notebook).
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,4 +17,255 @@ pub struct Kernel {
     pub language: String,
     /// Type of kernel (e.g., "script", "notebook")
     pub kernel_type: String,
-}
\ No newline at end of file
+}
+/// Contents of a `kernel-metadata.json` file.
+///
+/// This is the same format used by the official Kaggle CLI for `kernels pull`
+/// and `kernels push`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KernelMetadata {
+    /// Kernel reference in `owner/slug` format
+    pub id: String,
+    /// Numeric kernel identifier, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_no: Option<i64>,
+    /// Kernel title
+    pub title: String,
+    /// Path of the source file, relative to the metadata file
+    pub code_file: String,
+    /// Programming language (e.g., "python", "r")
+    pub language: String,
+    /// Type of kernel (e.g., "script", "notebook")
+    pub kernel_type: String,
+    /// Whether the kernel is private
+    #[serde(default = "default_true")]
+    pub is_private: bool,
+    /// Whether the kernel runs with a GPU
+    #[serde(default)]
+    pub enable_gpu: bool,
+    /// Whether the kernel runs with a TPU
+    #[serde(default)]
+    pub enable_tpu: bool,
+    /// Whether the kernel has internet access
+    #[serde(default = "default_true")]
+    pub enable_internet: bool,
+    /// Attached datasets in `owner/slug` format
+    #[serde(default)]
+    pub dataset_sources: Vec<String>,
+    /// Attached competitions by slug
+    #[serde(default)]
+    pub competition_sources: Vec<String>,
+    /// Attached kernels in `owner/slug` format
+    #[serde(default)]
+    pub kernel_sources: Vec<String>,
+    /// Attached models in `owner/model/framework/variation` format
+    #[serde(default)]
+    pub model_sources: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Kernel metadata returned by the pull endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KernelPullMetadata {
+    /// Numeric kernel identifier
+    pub id: Option<i64>,
+    /// Kernel reference in `owner/slug` format
+    #[serde(rename = "ref")]
+    pub ref_: String,
+    /// Kernel title
+    pub title: String,
+    /// Programming language
+    pub language: Option<String>,
+    /// Type of kernel
+    pub kernel_type: Option<String>,
+    /// Whether the kernel is private
+    #[serde(default)]
+    pub is_private: bool,
+    /// Whether the kernel runs with a GPU
+    #[serde(default)]
+    pub enable_gpu: bool,
+    /// Whether the kernel runs with a TPU
+    #[serde(default)]
+    pub enable_tpu: bool,
+    /// Whether the kernel has internet access
+    #[serde(default)]
+    pub enable_internet: bool,
+    /// Attached datasets
+    #[serde(default)]
+    pub dataset_data_sources: Vec<String>,
+    /// Attached competitions
+    #[serde(default)]
+    pub competition_data_sources: Vec<String>,
+    /// Attached kernels
+    #[serde(default)]
+    pub kernel_data_sources: Vec<String>,
+    /// Attached models
+    #[serde(default)]
+    pub model_data_sources: Vec<String>,
+    /// Version the metadata (including the data sources) belongs to
+    #[serde(default)]
+    pub current_version_number: Option<i32>,
+}
+
+/// Kernel source returned by the pull endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KernelBlob {
+    /// Kernel source code (notebook JSON for notebook kernels)
+    pub source: String,
+    /// Programming language
+    pub language: String,
+    /// Type of kernel
+    pub kernel_type: String,
+}
+
+/// Response from the kernel pull endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KernelPullResponse {
+    /// Kernel metadata
+    pub metadata: KernelPullMetadata,
+    /// Kernel source
+    pub blob: KernelBlob,
+}
+
+impl KernelPullResponse {
+    /// Converts the pulled metadata into `kernel-metadata.json` form.
+    pub fn to_metadata(&self, code_file: String) -> KernelMetadata {
+        let meta = &self.metadata;
+        KernelMetadata {
+            id: meta.ref_.clone(),
+            id_no: meta.id,
+            title: meta.title.clone(),
+            code_file,
+            language: self.blob.language.clone(),
+            kernel_type: self.blob.kernel_type.clone(),
+            is_private: meta.is_private,
+            enable_gpu: meta.enable_gpu,
+            enable_tpu: meta.enable_tpu,
+            enable_internet: meta.enable_internet,
+            dataset_sources: meta.dataset_data_sources.clone(),
+            competition_sources: meta.competition_data_sources.clone(),
+            kernel_sources: meta.kernel_data_sources.clone(),
+            model_sources: meta.model_data_sources.clone(),
+        }
+    }
+}
+
+/// Files written by pulling a kernel to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PulledKernel {
+    /// Path of the written source file
+    pub source_file: PathBuf,
+    /// Path of the written `kernel-metadata.json`, if requested
+    pub metadata_file: Option<PathBuf>,
+}
+
+/// Options applied to kernel source before it is pushed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KernelPushOptions {
+    /// Remove all cell outputs and execution counts from notebooks
+    pub strip_outputs: bool,
+    /// Remove saved widget state from notebooks
+    pub remove_widget_state: bool,
+    /// Push even if potential secrets are found
+    pub allow_secrets: bool,
+}
+
+/// Request body for the kernel push endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KernelPushRequest {
+    /// Numeric kernel identifier, when updating an existing kernel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    /// Kernel reference in `owner/slug` format
+    pub slug: String,
+    /// Kernel title
+    pub new_title: String,
+    /// Kernel source code
+    pub text: String,
+    /// Programming language
+    pub language: String,
+    /// Type of kernel
+    pub kernel_type: String,
+    /// Whether the kernel is private
+    pub is_private: bool,
+    /// Whether the kernel runs with a GPU
+    pub enable_gpu: bool,
+    /// Whether the kernel runs with a TPU
+    pub enable_tpu: bool,
+    /// Whether the kernel has internet access
+    pub enable_internet: bool,
+    /// Attached datasets
+    pub dataset_data_sources: Vec<String>,
+    /// Attached competitions
+    pub competition_data_sources: Vec<String>,
+    /// Attached kernels
+    pub kernel_data_sources: Vec<String>,
+    /// Attached models
+    pub model_data_sources: Vec<String>,
+}
+
+/// Response from the kernel push endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KernelPushResponse {
+    /// Kernel reference in `owner/slug` format
+    #[serde(rename = "ref")]
+    pub ref_: Option<String>,
+    /// Full URL to the kernel page
+    pub url: Option<String>,
+    /// Version number created by the push
+    pub version_number: Option<i32>,
+    /// Error message, if the push was rejected
+    pub error: Option<String>,
+}
+
+/// A single version of a kernel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KernelVersion {
+    /// Version number, starting at 1
+    pub version_number: i32,
+    /// Version title
+    pub title: Option<String>,
+    /// When the version was created
+    #[serde(alias = "dateCreated")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Run status (e.g., "complete", "error", "running")
+    pub status: Option<String>,
+    /// Run time in seconds
+    #[serde(alias = "runTime")]
+    pub run_time_seconds: Option<f64>,
+}
+
+/// Source file format used when pulling or pushing kernels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KernelSourceFormat {
+    /// Keep the kernel's own format
+    Auto,
+    /// Jupyter notebook (`.ipynb`)
+    Notebook,
+    /// Percent-format script (`.py` / `.R`)
+    Script,
+}
+
+impl std::str::FromStr for KernelSourceFormat {
+    type Err = super::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "notebook" | "ipynb" => Ok(Self::Notebook),
+            "script" => Ok(Self::Script),
+            _ => Err(super::Error::InvalidParameter(format!(
+                "Unknown kernel format '{}' (expected auto, notebook or script)",
+                s
+            ))),
+        }
+    }
+}
@@ -19,9 +19,18 @@ pub mod kernel;
 /// Model-related types
 pub mod model;
 
+/// Parsed, validated references to Kaggle entities
+pub mod refs;
+
+/// File upload types
+pub mod upload;
+
 /// Configuration types
 pub mod config;
 
+/// Secret values that stay out of logs
+pub mod secret;
+
 /// Error types and result aliases
 pub mod error;
 
@@ -34,5 +43,8 @@ pub use competition::*;
 pub use dataset::*;
 pub use kernel::*;
 pub use model::*;
+pub use refs::*;
+pub use upload::*;
 pub use config::*;
+pub use secret::*;
 pub use error::*;
\ No newline at end of file
@@ -1,16 +1,509 @@
 //! Model-related types.
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// Represents a Kaggle model.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Model {
     /// Unique model identifier
-    pub id: String,
+    pub id: i64,
+    /// Model reference in `owner/model-slug` format
+    #[serde(rename = "ref", default)]
+    pub ref_: String,
     /// Model title
     pub title: String,
     /// Model subtitle/description
     pub subtitle: Option<String>,
     /// Author username
     pub author: String,
-}
\ No newline at end of file
+    /// Model slug
+    #[serde(default)]
+    pub slug: String,
+    /// Full URL to the model page
+    pub url: Option<String>,
+    /// Whether the model is private
+    #[serde(default)]
+    pub is_private: bool,
+    /// Model card in markdown
+    pub description: Option<String>,
+    /// License of the model
+    pub license_name: Option<String>,
+    /// Tags attached to the model
+    #[serde(default)]
+    pub tags: Vec<ModelTag>,
+    /// Framework/variation instances of the model
+    #[serde(default)]
+    pub instances: Vec<ModelInstance>,
+    /// Number of downloads
+    pub download_count: Option<i64>,
+    /// Number of votes
+    pub vote_count: Option<i64>,
+    /// When the model was published
+    pub publish_time: Option<DateTime<Utc>>,
+    /// Where the model comes from (paper, repository, ...)
+    pub provenance_sources: Option<String>,
+}
+
+/// A tag attached to a model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelTag {
+    /// Tag reference (e.g., "computer-vision")
+    #[serde(rename = "ref", default)]
+    pub ref_: String,
+    /// Display name of the tag
+    pub name: String,
+}
+
+/// A framework/variation instance of a model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInstance {
+    /// Unique instance identifier
+    pub id: i64,
+    /// Variation slug (e.g., "2b-it")
+    pub slug: String,
+    /// Framework of the instance
+    pub framework: ModelFramework,
+    /// Whether the instance can be fine-tuned
+    #[serde(default)]
+    pub fine_tunable: bool,
+    /// License of the instance
+    pub license_name: Option<String>,
+    /// Overview of the variation in markdown
+    pub overview: Option<String>,
+    /// Usage instructions in markdown
+    pub usage: Option<String>,
+    /// Datasets or sources the instance was trained on
+    #[serde(default)]
+    pub training_data: Vec<String>,
+    /// Instance type (e.g., "Unspecified", "BaseModel", "KaggleVariant")
+    pub model_instance_type: Option<String>,
+    /// Latest version number
+    pub version_number: Option<i32>,
+    /// Full URL to the instance page
+    pub url: Option<String>,
+}
+
+/// Machine learning frameworks supported for model instances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelFramework {
+    /// TensorFlow 1
+    TensorFlow1,
+    /// TensorFlow 2
+    TensorFlow2,
+    /// TensorFlow Lite
+    TfLite,
+    /// TensorFlow.js
+    TfJs,
+    /// PyTorch
+    PyTorch,
+    /// JAX
+    Jax,
+    /// Flax
+    Flax,
+    /// PAX
+    Pax,
+    /// Keras
+    Keras,
+    /// Hugging Face Transformers
+    Transformers,
+    /// scikit-learn
+    ScikitLearn,
+    /// XGBoost
+    Xgboost,
+    /// MaxText
+    MaxText,
+    /// gemma.cpp
+    GemmaCpp,
+    /// GGML
+    Ggml,
+    /// GGUF
+    Gguf,
+    /// Coral
+    Coral,
+    /// TensorRT-LLM
+    TensorRtLlm,
+    /// ONNX
+    Onnx,
+    /// Any other framework
+    #[serde(other)]
+    Other,
+}
+
+impl ModelFramework {
+    /// Returns the name used by the Kaggle API and in instance references.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TensorFlow1 => "tensorFlow1",
+            Self::TensorFlow2 => "tensorFlow2",
+            Self::TfLite => "tfLite",
+            Self::TfJs => "tfJs",
+            Self::PyTorch => "pyTorch",
+            Self::Jax => "jax",
+            Self::Flax => "flax",
+            Self::Pax => "pax",
+            Self::Keras => "keras",
+            Self::Transformers => "transformers",
+            Self::ScikitLearn => "scikitLearn",
+            Self::Xgboost => "xgboost",
+            Self::MaxText => "maxText",
+            Self::GemmaCpp => "gemmaCpp",
+            Self::Ggml => "ggml",
+            Self::Gguf => "gguf",
+            Self::Coral => "coral",
+            Self::TensorRtLlm => "tensorRtLlm",
+            Self::Onnx => "onnx",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ModelFramework {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ModelFramework {
+    type Err = super::Error;
+
+    /// Parses a framework name case-insensitively (e.g., "pytorch" or "pyTorch").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        let frameworks = [
+            Self::TensorFlow1,
+            Self::TensorFlow2,
+            Self::TfLite,
+            Self::TfJs,
+            Self::PyTorch,
+            Self::Jax,
+            Self::Flax,
+            Self::Pax,
+            Self::Keras,
+            Self::Transformers,
+            Self::ScikitLearn,
+            Self::Xgboost,
+            Self::MaxText,
+            Self::GemmaCpp,
+            Self::Ggml,
+            Self::Gguf,
+            Self::Coral,
+            Self::TensorRtLlm,
+            Self::Onnx,
+            Self::Other,
+        ];
+        frameworks
+            .into_iter()
+            .find(|framework| framework.as_str().to_ascii_lowercase() == lower)
+            .ok_or_else(|| super::Error::InvalidParameter(format!("Unknown model framework '{}'", s)))
+    }
+}
+
+/// Response from the model list endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelListResponse {
+    /// Models on this page
+    #[serde(default)]
+    pub models: Vec<Model>,
+    /// Token for the next page, empty on the last page
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+}
+
+/// Contents of a `model-metadata.json` file.
+///
+/// This is the same format used by the official Kaggle CLI for
+/// `models init`, `models create` and `models update`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelMetadata {
+    /// Owner (user or organization) of the model
+    pub owner_slug: String,
+    /// Model title
+    pub title: String,
+    /// Model slug
+    pub slug: String,
+    /// Model subtitle
+    #[serde(default)]
+    pub subtitle: String,
+    /// Whether the model is private
+    #[serde(default = "default_true")]
+    pub is_private: bool,
+    /// Model card in markdown
+    #[serde(default)]
+    pub description: String,
+    /// When the model was published, if different from the upload time
+    #[serde(default, deserialize_with = "deserialize_optional_time")]
+    pub publish_time: Option<DateTime<Utc>>,
+    /// Where the model comes from (paper, repository, ...)
+    #[serde(default)]
+    pub provenance_sources: String,
+}
+
+/// Placeholder prefix used in generated metadata templates.
+pub const METADATA_PLACEHOLDER: &str = "INSERT_";
+
+impl ModelMetadata {
+    /// Returns a template with placeholders, as written by `model_initialize_metadata`.
+    pub fn template() -> Self {
+        Self {
+            owner_slug: "INSERT_OWNER_SLUG_HERE".to_string(),
+            title: "INSERT_TITLE_HERE".to_string(),
+            slug: "INSERT_SLUG_HERE".to_string(),
+            subtitle: String::new(),
+            is_private: true,
+            description: "# Model Summary\n\n# Model Characteristics\n\n# Data Overview\n\n# Evaluation Results\n"
+                .to_string(),
+            publish_time: None,
+            provenance_sources: String::new(),
+        }
+    }
+
+    /// Checks that required fields are filled in.
+    pub fn validate(&self) -> Result<(), super::Error> {
+        for (name, value) in [
+            ("ownerSlug", &self.owner_slug),
+            ("title", &self.title),
+            ("slug", &self.slug),
+        ] {
+            if value.is_empty() || value.starts_with(METADATA_PLACEHOLDER) {
+                return Err(super::Error::InvalidParameter(format!(
+                    "Field '{}' in model-metadata.json must be filled in",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the model reference in `owner/model-slug` format.
+    pub fn model_ref(&self) -> String {
+        format!("{}/{}", self.owner_slug, self.slug)
+    }
+
+    /// Returns the camelCase names of the fields that differ from `model`.
+    pub fn changed_fields(&self, model: &Model) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.title != model.title {
+            changed.push("title");
+        }
+        if self.subtitle != model.subtitle.clone().unwrap_or_default() {
+            changed.push("subtitle");
+        }
+        if self.is_private != model.is_private {
+            changed.push("isPrivate");
+        }
+        if self.description != model.description.clone().unwrap_or_default() {
+            changed.push("description");
+        }
+        if self.publish_time.is_some() && self.publish_time != model.publish_time {
+            changed.push("publishTime");
+        }
+        if self.provenance_sources != model.provenance_sources.clone().unwrap_or_default() {
+            changed.push("provenanceSources");
+        }
+        changed
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Treats an empty string as a missing timestamp, as the CLI template does.
+fn deserialize_optional_time<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(s) if s.is_empty() => Ok(None),
+        Some(s) => DateTime::parse_from_rfc3339(&s)
+            .map(|time| Some(time.with_timezone(&Utc)))
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// Request body for updating a model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUpdateRequest {
+    /// The new metadata values
+    #[serde(flatten)]
+    pub metadata: ModelMetadata,
+    /// Comma-separated names of the fields to update
+    pub update_mask: String,
+}
+
+/// Response from the model create, update and delete endpoints.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelResponse {
+    /// Model identifier
+    pub id: Option<i64>,
+    /// Model reference in `owner/model-slug` format
+    #[serde(rename = "ref")]
+    pub ref_: Option<String>,
+    /// Full URL to the model page
+    pub url: Option<String>,
+    /// Error message, if the request was rejected
+    pub error: Option<String>,
+}
+
+/// Contents of a `model-instance-metadata.json` file.
+///
+/// This is the same format used by the official Kaggle CLI for
+/// `models instances init`, `create` and `update`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInstanceMetadata {
+    /// Owner (user or organization) of the model
+    pub owner_slug: String,
+    /// Slug of the existing model
+    pub model_slug: String,
+    /// Variation slug of the instance
+    pub instance_slug: String,
+    /// Framework of the instance
+    pub framework: ModelFramework,
+    /// Overview of the variation in markdown
+    #[serde(default)]
+    pub overview: String,
+    /// Usage instructions in markdown
+    #[serde(default)]
+    pub usage: String,
+    /// License of the instance (e.g., "Apache 2.0")
+    pub license_name: String,
+    /// Whether the instance can be fine-tuned
+    #[serde(default)]
+    pub fine_tunable: bool,
+    /// Datasets or sources the instance was trained on
+    #[serde(default)]
+    pub training_data: Vec<String>,
+}
+
+impl ModelInstanceMetadata {
+    /// Returns a template with placeholders, as written by
+    /// `model_instance_initialize_metadata`.
+    pub fn template() -> Self {
+        Self {
+            owner_slug: "INSERT_OWNER_SLUG_HERE".to_string(),
+            model_slug: "INSERT_EXISTING_MODEL_SLUG_HERE".to_string(),
+            instance_slug: "INSERT_INSTANCE_SLUG_HERE".to_string(),
+            framework: ModelFramework::Other,
+            overview: String::new(),
+            usage: "# Model Format\n\n# Training Data\n\n# Model Inputs\n\n# Model Outputs\n\n# Model Usage\n"
+                .to_string(),
+            license_name: "Apache 2.0".to_string(),
+            fine_tunable: false,
+            training_data: Vec::new(),
+        }
+    }
+
+    /// Checks that required fields are filled in.
+    pub fn validate(&self) -> Result<(), super::Error> {
+        for (name, value) in [
+            ("ownerSlug", &self.owner_slug),
+            ("modelSlug", &self.model_slug),
+            ("instanceSlug", &self.instance_slug),
+            ("licenseName", &self.license_name),
+        ] {
+            if value.is_empty() || value.starts_with(METADATA_PLACEHOLDER) {
+                return Err(super::Error::InvalidParameter(format!(
+                    "Field '{}' in model-instance-metadata.json must be filled in",
+                    name
+                )));
+            }
+        }
+        if self.framework == ModelFramework::Other {
+            return Err(super::Error::InvalidParameter(
+                "Field 'framework' in model-instance-metadata.json must name a supported framework (e.g., pyTorch)".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the instance reference in `owner/model/framework/variation` format.
+    pub fn instance_ref(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.owner_slug, self.model_slug, self.framework, self.instance_slug
+        )
+    }
+
+    /// Returns the camelCase names of the updatable fields that differ from
+    /// `instance`.
+    pub fn changed_fields(&self, instance: &ModelInstance) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.overview != instance.overview.clone().unwrap_or_default() {
+            changed.push("overview");
+        }
+        if self.usage != instance.usage.clone().unwrap_or_default() {
+            changed.push("usage");
+        }
+        if Some(&self.license_name) != instance.license_name.as_ref() {
+            changed.push("licenseName");
+        }
+        if self.fine_tunable != instance.fine_tunable {
+            changed.push("fineTunable");
+        }
+        if self.training_data != instance.training_data {
+            changed.push("trainingData");
+        }
+        changed
+    }
+}
+
+/// Request body for updating a model instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInstanceUpdateRequest {
+    /// The new metadata values
+    #[serde(flatten)]
+    pub metadata: ModelInstanceMetadata,
+    /// Comma-separated names of the fields to update
+    pub update_mask: String,
+}
+
+/// A version of a model instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInstanceVersion {
+    /// Unique version identifier
+    pub id: Option<i64>,
+    /// Version number within the instance
+    pub version_number: i32,
+    /// Notes describing the version
+    pub version_notes: Option<String>,
+    /// When the version was created
+    #[serde(alias = "creationDate")]
+    pub create_time: Option<DateTime<Utc>>,
+    /// Processing status (e.g., "ready")
+    pub status: Option<String>,
+}
+
+/// Response from the model instance version list endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInstanceVersionListResponse {
+    /// Versions on this page, newest first
+    #[serde(default)]
+    pub versions: Vec<ModelInstanceVersion>,
+    /// Token for fetching the next page, empty on the last page
+    pub next_page_token: Option<String>,
+}
+
+/// Request body for creating a new model instance version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInstanceVersionRequest {
+    /// Notes describing the version
+    pub version_notes: String,
+    /// Uploaded files at the top level of the version
+    pub files: Vec<super::UploadFile>,
+    /// Uploaded subdirectories
+    pub directories: Vec<super::UploadDirectory>,
+}
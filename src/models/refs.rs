@@ -0,0 +1,341 @@
+//! Kaggle handle types.
+//!
+//! Each type parses and validates the handle Kaggle uses for an entity, so a
+//! malformed reference is rejected with `Error::InvalidParameter` before any
+//! request is made. Besides the plain handle (e.g., `owner/slug`), the full
+//! `https://www.kaggle.com/...` URL of the entity's page is accepted.
+
+use super::{Error, ModelFramework};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A competition, identified by its slug (e.g., `titanic`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompetitionRef {
+    /// Competition slug
+    pub slug: String,
+}
+
+/// A dataset, identified as `owner/slug[/versions/N]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DatasetRef {
+    /// Owner (user or organization) of the dataset
+    pub owner: String,
+    /// Dataset slug
+    pub slug: String,
+    /// Specific version, or `None` for the latest
+    pub version: Option<i32>,
+}
+
+/// A kernel (notebook), identified as `owner/slug`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KernelRef {
+    /// Owner of the kernel
+    pub owner: String,
+    /// Kernel slug
+    pub slug: String,
+}
+
+/// A model, identified as `owner/slug`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModelRef {
+    /// Owner (user or organization) of the model
+    pub owner: String,
+    /// Model slug
+    pub slug: String,
+}
+
+/// A model instance, identified as `owner/model/framework/variation[/version]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModelInstanceRef {
+    /// Owner (user or organization) of the model
+    pub owner: String,
+    /// Model slug
+    pub model: String,
+    /// Framework of the instance
+    pub framework: ModelFramework,
+    /// Variation slug
+    pub variation: String,
+    /// Specific version, or `None` for the latest
+    pub version: Option<i32>,
+}
+
+impl ModelInstanceRef {
+    /// Returns the model the instance belongs to.
+    pub fn model_ref(&self) -> ModelRef {
+        ModelRef {
+            owner: self.owner.clone(),
+            slug: self.model.clone(),
+        }
+    }
+}
+
+impl FromStr for CompetitionRef {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (segments, is_url) = split_handle(s, &["competitions", "c"])?;
+        match segments.as_slice() {
+            [slug] => Ok(Self { slug: valid(slug, s, "competition")? }),
+            // Competition pages have tabs such as /data and /leaderboard.
+            [slug, ..] if is_url => Ok(Self { slug: valid(slug, s, "competition")? }),
+            _ => Err(invalid("competition", s, "competition-slug")),
+        }
+    }
+}
+
+impl FromStr for DatasetRef {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "owner/slug or owner/slug/versions/N";
+        let (segments, is_url) = split_handle(s, &["datasets"])?;
+        let (owner, slug, rest) = match segments.as_slice() {
+            [owner, slug, rest @ ..] => (owner, slug, rest),
+            _ => return Err(invalid("dataset", s, EXPECTED)),
+        };
+        let version = match rest {
+            [] => None,
+            ["versions", version, ..] if is_url || rest.len() == 2 => {
+                Some(parse_version(version, s, "dataset")?)
+            }
+            _ if is_url => None,
+            _ => return Err(invalid("dataset", s, EXPECTED)),
+        };
+        Ok(Self {
+            owner: valid(owner, s, "dataset")?,
+            slug: valid(slug, s, "dataset")?,
+            version,
+        })
+    }
+}
+
+impl FromStr for KernelRef {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (segments, is_url) = split_handle(s, &["code"])?;
+        match segments.as_slice() {
+            [owner, slug] => Ok(Self {
+                owner: valid(owner, s, "kernel")?,
+                slug: valid(slug, s, "kernel")?,
+            }),
+            // Kernel pages have tabs such as /notebook and /log.
+            [owner, slug, ..] if is_url => Ok(Self {
+                owner: valid(owner, s, "kernel")?,
+                slug: valid(slug, s, "kernel")?,
+            }),
+            _ => Err(invalid("kernel", s, "owner/kernel-slug")),
+        }
+    }
+}
+
+impl FromStr for ModelRef {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (segments, _) = split_handle(s, &["models"])?;
+        match segments.as_slice() {
+            [owner, slug] => Ok(Self {
+                owner: valid(owner, s, "model")?,
+                slug: valid(slug, s, "model")?,
+            }),
+            _ => Err(invalid("model", s, "owner/model-slug")),
+        }
+    }
+}
+
+impl FromStr for ModelInstanceRef {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "owner/model-slug/framework/variation-slug[/version]";
+        let (segments, _) = split_handle(s, &["models"])?;
+        let (owner, model, framework, variation, version) = match segments.as_slice() {
+            [owner, model, framework, variation] => (owner, model, framework, variation, None),
+            [owner, model, framework, variation, version] => {
+                (owner, model, framework, variation, Some(parse_version(version, s, "model instance")?))
+            }
+            _ => return Err(invalid("model instance", s, EXPECTED)),
+        };
+        Ok(Self {
+            owner: valid(owner, s, "model instance")?,
+            model: valid(model, s, "model instance")?,
+            framework: framework.parse()?,
+            variation: valid(variation, s, "model instance")?,
+            version,
+        })
+    }
+}
+
+impl fmt::Display for CompetitionRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.slug)
+    }
+}
+
+impl fmt::Display for DatasetRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.owner, self.slug)?;
+        if let Some(version) = self.version {
+            write!(f, "/versions/{}", version)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for KernelRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.owner, self.slug)
+    }
+}
+
+impl fmt::Display for ModelRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.owner, self.slug)
+    }
+}
+
+impl fmt::Display for ModelInstanceRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}/{}",
+            self.owner, self.model, self.framework, self.variation
+        )?;
+        if let Some(version) = self.version {
+            write!(f, "/{}", version)?;
+        }
+        Ok(())
+    }
+}
+
+/// Implements string-based serde and JSON schema support for a handle type.
+macro_rules! string_handle {
+    ($type:ty, $description:literal) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+
+        impl schemars::JsonSchema for $type {
+            fn is_referenceable() -> bool {
+                false
+            }
+
+            fn schema_name() -> String {
+                stringify!($type).to_string()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                let mut schema = SchemaObject {
+                    instance_type: Some(InstanceType::String.into()),
+                    ..Default::default()
+                };
+                schema.metadata().description = Some($description.to_string());
+                schema.into()
+            }
+        }
+    };
+}
+
+string_handle!(CompetitionRef, "Competition slug (e.g., titanic) or competition URL");
+string_handle!(DatasetRef, "Dataset as owner/slug[/versions/N] or dataset URL");
+string_handle!(KernelRef, "Kernel as owner/kernel-slug or notebook URL");
+string_handle!(ModelRef, "Model as owner/model-slug or model URL");
+string_handle!(
+    ModelInstanceRef,
+    "Model instance as owner/model-slug/framework/variation-slug[/version] or instance URL"
+);
+
+/// Splits a handle or Kaggle URL into its path segments.
+///
+/// For URLs, the scheme, host, query and fragment are removed, as is the
+/// leading section such as `datasets`, which must be one of `sections`.
+/// Returns the segments and whether the input was a URL.
+fn split_handle<'a>(input: &'a str, sections: &[&str]) -> Result<(Vec<&'a str>, bool), Error> {
+    let trimmed = input.trim();
+    let Some(rest) = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))
+    else {
+        return Ok((trimmed.split('/').collect(), false));
+    };
+
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    if !matches!(host, "www.kaggle.com" | "kaggle.com") {
+        return Err(Error::InvalidParameter(format!(
+            "'{}' is not a kaggle.com URL",
+            input
+        )));
+    }
+    let path = path
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    let mut segments: Vec<&str> = path.split('/').collect();
+    // Another section (e.g. /code for a dataset) or a profile page is a
+    // different kind of entity.
+    if !sections.contains(&segments[0]) {
+        return Err(Error::InvalidParameter(format!(
+            "'{}' is not a kaggle.com/{} URL",
+            input,
+            sections.join(" or kaggle.com/")
+        )));
+    }
+    // A bare section URL (e.g. /competitions) leaves no segments.
+    segments.remove(0);
+    Ok((segments, true))
+}
+
+/// Checks that a handle segment is a non-empty slug.
+///
+/// Segments made only of dots are rejected, as `.` and `..` would be
+/// resolved as relative paths in the request URL.
+fn valid(segment: &str, input: &str, kind: &str) -> Result<String, Error> {
+    let is_valid = !segment.is_empty()
+        && !segment.chars().all(|c| c == '.')
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if is_valid {
+        Ok(segment.to_string())
+    } else {
+        Err(Error::InvalidParameter(format!(
+            "Invalid {} reference '{}' ('{}' is not a valid slug)",
+            kind, input, segment
+        )))
+    }
+}
+
+/// Parses a positive version number.
+fn parse_version(segment: &str, input: &str, kind: &str) -> Result<i32, Error> {
+    match segment.parse::<i32>() {
+        Ok(version) if version > 0 => Ok(version),
+        _ => Err(Error::InvalidParameter(format!(
+            "Invalid {} reference '{}' ('{}' is not a version number)",
+            kind, input, segment
+        ))),
+    }
+}
+
+/// Builds the error for a reference with the wrong shape.
+fn invalid(kind: &str, input: &str, expected: &str) -> Error {
+    Error::InvalidParameter(format!(
+        "Invalid {} reference '{}' (expected {})",
+        kind, input, expected
+    ))
+}
@@ -0,0 +1,54 @@
+//! File upload types.
+//!
+//! Files are uploaded to Kaggle in two steps: a blob upload is started to get
+//! a token and a signed URL, the file is sent to that URL, and the token is
+//! then referenced when creating a dataset or model version.
+
+use serde::{Deserialize, Serialize};
+
+/// Request to start a blob upload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobUploadRequest {
+    /// Kind of object the file belongs to (e.g., "model", "dataset")
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// File name
+    pub name: String,
+    /// Size of the file in bytes
+    pub content_length: u64,
+    /// Last modification time of the file
+    pub last_modified_epoch_seconds: i64,
+}
+
+/// Response to a blob upload request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobUploadResponse {
+    /// Token identifying the uploaded file
+    pub token: String,
+    /// Signed URL to send the file contents to
+    pub create_url: String,
+}
+
+/// An uploaded file, referenced by its blob token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadFile {
+    /// Token returned by the blob upload
+    pub token: String,
+}
+
+/// A directory of uploaded files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadDirectory {
+    /// Directory name
+    pub name: String,
+    /// Files directly in the directory
+    #[serde(default)]
+    pub files: Vec<UploadFile>,
+    /// Subdirectories
+    #[serde(default)]
+    pub directories: Vec<UploadDirectory>,
+}
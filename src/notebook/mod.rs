@@ -0,0 +1,481 @@
+//! Conversion between Jupyter notebooks and percent-format scripts.
+//!
+//! Kaggle runs kernels as notebooks (`.ipynb`), but notebooks are awkward to
+//! review in version control. This module converts notebooks to and from the
+//! "percent" script format understood by Jupytext, VS Code and PyCharm, where
+//! every cell starts with a `# %%` marker line:
+//!
+//! ```text
+//! # ---
+//! # jupyter: {"kernelspec":{"display_name":"Python 3","language":"python","name":"python3"}}
+//! # ---
+//!
+//! # %% [markdown]
+//! # # Exploratory analysis
+//!
+//! # %% tags=["parameters"]
+//! learning_rate = 0.1
+//! ```
+//!
+//! Markdown and raw cells are written as comments, cell metadata is written as
+//! `key=value` pairs (with JSON values) on the marker line, and notebook
+//! metadata is kept in the header. Cell outputs are not represented in scripts.
+
+use crate::models::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+mod output;
+mod sanitize;
+
+#[cfg(test)]
+mod tests;
+
+pub use output::{extract_outputs, html_tables_to_markdown, CellOutputs, OutputContent};
+pub use sanitize::{
+    sanitize_notebook, scan_notebook, scan_text, SanitizeOptions, SecretFinding,
+};
+
+/// Marker that starts a cell in a percent-format script.
+const CELL_MARKER: &str = "# %%";
+
+/// Delimiter line around the notebook metadata header.
+const HEADER_DELIMITER: &str = "# ---";
+
+/// Key under which notebook metadata is stored in the header.
+const HEADER_KEY: &str = "# jupyter:";
+
+/// Represents a Jupyter notebook in nbformat 4.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notebook {
+    /// Notebook cells, in order
+    pub cells: Vec<Cell>,
+    /// Notebook-level metadata (kernelspec, language_info, ...)
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+    /// Major nbformat version
+    pub nbformat: u32,
+    /// Minor nbformat version
+    pub nbformat_minor: u32,
+}
+
+/// The kind of a notebook cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CellType {
+    /// Executable source code
+    Code,
+    /// Markdown text
+    Markdown,
+    /// Raw text passed through unchanged
+    Raw,
+}
+
+/// Represents a single notebook cell.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cell {
+    /// The kind of cell
+    pub cell_type: CellType,
+    /// Cell identifier (nbformat 4.5 and later)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Cell metadata (tags, collapsed state, ...)
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+    /// Cell source as a single string
+    #[serde(
+        serialize_with = "serialize_source",
+        deserialize_with = "deserialize_source"
+    )]
+    pub source: String,
+    /// Remaining fields, such as `outputs`, `execution_count` and `attachments`
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Cell {
+    /// Creates a code cell with no outputs.
+    pub fn code(source: impl Into<String>) -> Self {
+        let mut extra = Map::new();
+        extra.insert("execution_count".to_string(), Value::Null);
+        extra.insert("outputs".to_string(), Value::Array(vec![]));
+        Self {
+            cell_type: CellType::Code,
+            id: None,
+            metadata: Map::new(),
+            source: source.into(),
+            extra,
+        }
+    }
+
+    /// Creates a markdown cell.
+    pub fn markdown(source: impl Into<String>) -> Self {
+        Self {
+            cell_type: CellType::Markdown,
+            id: None,
+            metadata: Map::new(),
+            source: source.into(),
+            extra: Map::new(),
+        }
+    }
+
+    /// Creates a raw cell.
+    pub fn raw(source: impl Into<String>) -> Self {
+        Self {
+            cell_type: CellType::Raw,
+            ..Self::markdown(source)
+        }
+    }
+}
+
+/// Scripting languages that notebooks can be converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptLanguage {
+    /// Python (`.py`)
+    Python,
+    /// R (`.R`)
+    R,
+}
+
+impl ScriptLanguage {
+    /// Returns the language for a Kaggle kernel language name, if supported.
+    pub fn from_kaggle_language(language: &str) -> Option<Self> {
+        match language.to_ascii_lowercase().as_str() {
+            "python" => Some(Self::Python),
+            "r" => Some(Self::R),
+            _ => None,
+        }
+    }
+
+    /// Returns the language for a file extension (without the dot), if supported.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "py" => Some(Self::Python),
+            "R" | "r" => Some(Self::R),
+            _ => None,
+        }
+    }
+
+    /// Returns the file extension used for scripts in this language.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Python => "py",
+            Self::R => "R",
+        }
+    }
+
+    /// Returns the language name used by the Kaggle API.
+    pub fn kaggle_language(&self) -> &'static str {
+        match self {
+            Self::Python => "python",
+            Self::R => "r",
+        }
+    }
+
+    /// Returns default notebook metadata for this language.
+    fn default_metadata(&self) -> Map<String, Value> {
+        let metadata = match self {
+            Self::Python => serde_json::json!({
+                "kernelspec": {"display_name": "Python 3", "language": "python", "name": "python3"},
+                "language_info": {"name": "python"}
+            }),
+            Self::R => serde_json::json!({
+                "kernelspec": {"display_name": "R", "language": "R", "name": "ir"},
+                "language_info": {"name": "R"}
+            }),
+        };
+        match metadata {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Parses a notebook from its `.ipynb` JSON representation.
+pub fn parse_notebook(json: &str) -> Result<Notebook, Error> {
+    serde_json::from_str(json)
+        .map_err(|e| Error::NotebookError(format!("Invalid notebook JSON: {}", e)))
+}
+
+/// Serializes a notebook to pretty-printed `.ipynb` JSON.
+pub fn write_notebook(notebook: &Notebook) -> Result<String, Error> {
+    let mut json = serde_json::to_string_pretty(notebook)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Converts a notebook to a percent-format script.
+///
+/// # Arguments
+///
+/// * `notebook` - The notebook to convert
+///
+/// # Returns
+///
+/// Returns the script text. Outputs and execution counts are dropped; cell
+/// types, cell metadata and notebook metadata are preserved.
+pub fn notebook_to_script(notebook: &Notebook) -> String {
+    let mut script = String::new();
+
+    if !notebook.metadata.is_empty() {
+        script.push_str(HEADER_DELIMITER);
+        script.push('\n');
+        script.push_str(HEADER_KEY);
+        script.push(' ');
+        script.push_str(&Value::Object(notebook.metadata.clone()).to_string());
+        script.push('\n');
+        script.push_str(HEADER_DELIMITER);
+        script.push_str("\n\n");
+    }
+
+    for (index, cell) in notebook.cells.iter().enumerate() {
+        if index > 0 {
+            script.push('\n');
+        }
+
+        script.push_str(CELL_MARKER);
+        match cell.cell_type {
+            CellType::Code => {}
+            CellType::Markdown => script.push_str(" [markdown]"),
+            CellType::Raw => script.push_str(" [raw]"),
+        }
+        for (key, value) in &cell.metadata {
+            script.push_str(&format!(" {}={}", key, value));
+        }
+        script.push('\n');
+
+        let source = cell.source.trim_end_matches('\n');
+        match cell.cell_type {
+            CellType::Code => {
+                if !source.is_empty() {
+                    script.push_str(source);
+                    script.push('\n');
+                }
+            }
+            CellType::Markdown | CellType::Raw => {
+                for line in source.lines() {
+                    if line.is_empty() {
+                        script.push_str("#\n");
+                    } else {
+                        script.push_str("# ");
+                        script.push_str(line);
+                        script.push('\n');
+                    }
+                }
+            }
+        }
+    }
+
+    script
+}
+
+/// Converts a percent-format script to a notebook.
+///
+/// Text before the first `# %%` marker becomes a code cell. Scripts without
+/// a metadata header get a default kernelspec for `language`.
+///
+/// # Arguments
+///
+/// * `script` - The script text
+/// * `language` - The script language, used when the header has no metadata
+///
+/// # Returns
+///
+/// Returns the notebook, or `Error::NotebookError` if the header or a cell
+/// marker contains invalid metadata.
+pub fn script_to_notebook(script: &str, language: ScriptLanguage) -> Result<Notebook, Error> {
+    let mut lines = script.lines().peekable();
+    let mut metadata = None;
+
+    if lines.peek() == Some(&HEADER_DELIMITER) {
+        lines.next();
+        let mut found_end = false;
+        for line in lines.by_ref() {
+            if line == HEADER_DELIMITER {
+                found_end = true;
+                break;
+            }
+            if let Some(json) = line.strip_prefix(HEADER_KEY) {
+                match serde_json::from_str(json.trim()) {
+                    Ok(Value::Object(map)) => metadata = Some(map),
+                    _ => {
+                        return Err(Error::NotebookError(
+                            "Invalid notebook metadata in script header".to_string(),
+                        ))
+                    }
+                }
+            }
+        }
+        if !found_end {
+            return Err(Error::NotebookError(
+                "Unterminated metadata header in script".to_string(),
+            ));
+        }
+    }
+
+    let mut cells = Vec::new();
+    let mut current: Option<(CellType, Map<String, Value>)> = None;
+    let mut body: Vec<&str> = Vec::new();
+
+    for line in lines {
+        if let Some(rest) = parse_cell_marker(line) {
+            push_cell(&mut cells, current.take(), &body);
+            body.clear();
+            current = Some(parse_marker_options(rest)?);
+        } else {
+            body.push(line);
+        }
+    }
+    push_cell(&mut cells, current, &body);
+
+    Ok(Notebook {
+        cells,
+        metadata: metadata.unwrap_or_else(|| language.default_metadata()),
+        nbformat: 4,
+        nbformat_minor: 4,
+    })
+}
+
+/// Returns the text after the cell marker if `line` starts a new cell.
+fn parse_cell_marker(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix(CELL_MARKER)?;
+    if rest.is_empty() || rest.starts_with(' ') {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// Parses the cell type and metadata following a `# %%` marker.
+///
+/// Accepts an optional title, an optional `[markdown]`/`[md]`/`[raw]` type and
+/// `key=<json>` pairs, e.g. `# %% Setup [markdown] tags=["intro"]`.
+fn parse_marker_options(options: &str) -> Result<(CellType, Map<String, Value>), Error> {
+    let mut cell_type = CellType::Code;
+    let mut title = "";
+    let mut rest = options;
+
+    if let (Some(start), Some(end)) = (options.find('['), options.find(']')) {
+        // A bracket after the first `=` belongs to a metadata value.
+        if start < end && !options[..start].contains('=') {
+            cell_type = match &options[start + 1..end] {
+                "markdown" | "md" => CellType::Markdown,
+                "raw" => CellType::Raw,
+                kind => {
+                    return Err(Error::NotebookError(format!(
+                        "Unknown cell type in marker: [{}]",
+                        kind
+                    )))
+                }
+            };
+            title = options[..start].trim();
+            rest = &options[end + 1..];
+        }
+    }
+
+    let mut metadata = parse_marker_metadata(rest);
+    if !title.is_empty() {
+        metadata.insert("title".to_string(), Value::String(title.to_string()));
+    }
+    Ok((cell_type, metadata))
+}
+
+/// Parses space-separated `key=<json>` pairs from a cell marker line.
+///
+/// Text that is not a valid pair, such as `# %% Load data`, is kept as the
+/// cell title.
+fn parse_marker_metadata(text: &str) -> Map<String, Value> {
+    let mut metadata = Map::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        let pair = rest.find('=').and_then(|eq| {
+            let key = &rest[..eq];
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return None;
+            }
+            let mut values =
+                serde_json::Deserializer::from_str(&rest[eq + 1..]).into_iter::<Value>();
+            match values.next() {
+                Some(Ok(value)) => Some((key, value, eq + 1 + values.byte_offset())),
+                _ => None,
+            }
+        });
+
+        match pair {
+            Some((key, value, consumed)) => {
+                metadata.insert(key.to_string(), value);
+                rest = rest[consumed..].trim_start();
+            }
+            None => {
+                metadata.insert("title".to_string(), Value::String(rest.to_string()));
+                break;
+            }
+        }
+    }
+
+    metadata
+}
+
+/// Finishes the cell being parsed and appends it to `cells`.
+fn push_cell(
+    cells: &mut Vec<Cell>,
+    current: Option<(CellType, Map<String, Value>)>,
+    body: &[&str],
+) {
+    let end = body
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    let body = &body[..end];
+
+    let (cell_type, metadata) = match current {
+        Some(current) => current,
+        // Text before the first marker only becomes a cell if it has content.
+        None if body.is_empty() => return,
+        None => (CellType::Code, Map::new()),
+    };
+
+    let mut cell = match cell_type {
+        CellType::Code => Cell::code(body.join("\n")),
+        CellType::Markdown | CellType::Raw => {
+            let source = body
+                .iter()
+                .map(|line| {
+                    line.strip_prefix("# ")
+                        .or_else(|| line.strip_prefix('#'))
+                        .unwrap_or(line)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            if cell_type == CellType::Markdown {
+                Cell::markdown(source)
+            } else {
+                Cell::raw(source)
+            }
+        }
+    };
+    cell.metadata = metadata;
+    cells.push(cell);
+}
+
+/// Serializes cell source as a list of lines, as Jupyter itself does.
+fn serialize_source<S: Serializer>(source: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    let lines: Vec<&str> = source.split_inclusive('\n').collect();
+    lines.serialize(serializer)
+}
+
+/// Accepts cell source either as a single string or as a list of lines.
+fn deserialize_source<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Source {
+        Text(String),
+        Lines(Vec<String>),
+    }
+
+    Ok(match Source::deserialize(deserializer)? {
+        Source::Text(text) => text,
+        Source::Lines(lines) => lines.concat(),
+    })
+}
@@ -0,0 +1,328 @@
+//! Extraction of executed cell outputs from notebooks.
+//!
+//! Outputs are reduced to the forms an assistant can use directly: plain text,
+//! base64-encoded images and markdown (HTML tables such as pandas DataFrames
+//! are converted to markdown tables).
+
+use super::{CellType, Notebook};
+use serde_json::Value;
+
+/// Maximum length of a single text output before it is truncated.
+const MAX_TEXT_LENGTH: usize = 20_000;
+
+/// A single output of an executed cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputContent {
+    /// Plain text (stream output, `text/plain` results or error tracebacks)
+    Text(String),
+    /// Markdown, either from `text/markdown` or converted from an HTML table
+    Markdown(String),
+    /// A base64-encoded image
+    Image {
+        /// Base64-encoded image data
+        data: String,
+        /// MIME type of the image (e.g., "image/png")
+        mime_type: String,
+    },
+}
+
+/// The outputs of one code cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellOutputs {
+    /// Index of the cell in the notebook
+    pub cell_index: usize,
+    /// Execution count of the cell, if it was executed
+    pub execution_count: Option<i64>,
+    /// Outputs in the order they were produced
+    pub outputs: Vec<OutputContent>,
+}
+
+/// Extracts the outputs of all executed code cells.
+///
+/// Cells without outputs are skipped. Consecutive stream outputs of a cell are
+/// merged into a single text output.
+///
+/// # Arguments
+///
+/// * `notebook` - The notebook to read outputs from
+/// * `include_images` - Whether to include image outputs
+///
+/// # Returns
+///
+/// Returns the outputs grouped by cell.
+pub fn extract_outputs(notebook: &Notebook, include_images: bool) -> Vec<CellOutputs> {
+    notebook
+        .cells
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| cell.cell_type == CellType::Code)
+        .filter_map(|(cell_index, cell)| {
+            let raw_outputs = cell.extra.get("outputs")?.as_array()?;
+            let mut outputs = Vec::new();
+            let mut previous_was_stream = false;
+            for output in raw_outputs {
+                let Some(content) = convert_output(output, include_images) else {
+                    continue;
+                };
+                let stream = is_stream(output);
+                match (outputs.last_mut(), &content) {
+                    (Some(OutputContent::Text(previous)), OutputContent::Text(text))
+                        if stream && previous_was_stream =>
+                    {
+                        previous.push_str(text);
+                    }
+                    _ => outputs.push(content),
+                }
+                previous_was_stream = stream;
+            }
+            if outputs.is_empty() {
+                return None;
+            }
+            for output in &mut outputs {
+                if let OutputContent::Text(text) | OutputContent::Markdown(text) = output {
+                    truncate(text);
+                }
+            }
+            Some(CellOutputs {
+                cell_index,
+                execution_count: cell.extra.get("execution_count").and_then(Value::as_i64),
+                outputs,
+            })
+        })
+        .collect()
+}
+
+/// Returns whether `output` is a stream (stdout/stderr) output.
+fn is_stream(output: &Value) -> bool {
+    output.get("output_type").and_then(Value::as_str) == Some("stream")
+}
+
+/// Converts one nbformat output to its most useful representation.
+fn convert_output(output: &Value, include_images: bool) -> Option<OutputContent> {
+    match output.get("output_type")?.as_str()? {
+        "stream" => Some(OutputContent::Text(multiline(output.get("text")?))),
+        "error" => {
+            let traceback = output
+                .get("traceback")
+                .and_then(Value::as_array)
+                .map(|lines| {
+                    lines
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(strip_ansi)
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            if traceback.is_empty() {
+                let name = output.get("ename").and_then(Value::as_str).unwrap_or("Error");
+                let value = output.get("evalue").and_then(Value::as_str).unwrap_or("");
+                Some(OutputContent::Text(format!("{}: {}", name, value)))
+            } else {
+                Some(OutputContent::Text(traceback))
+            }
+        }
+        "execute_result" | "display_data" => {
+            let data = output.get("data")?.as_object()?;
+            if include_images {
+                for mime_type in ["image/png", "image/jpeg"] {
+                    if let Some(image) = data.get(mime_type) {
+                        let data: String = multiline(image)
+                            .chars()
+                            .filter(|c| !c.is_whitespace())
+                            .collect();
+                        return Some(OutputContent::Image {
+                            data,
+                            mime_type: mime_type.to_string(),
+                        });
+                    }
+                }
+            }
+            if let Some(html) = data.get("text/html") {
+                if let Some(markdown) = html_tables_to_markdown(&multiline(html)) {
+                    return Some(OutputContent::Markdown(markdown));
+                }
+            }
+            if let Some(markdown) = data.get("text/markdown") {
+                return Some(OutputContent::Markdown(multiline(markdown)));
+            }
+            data.get("text/plain")
+                .map(|text| OutputContent::Text(multiline(text)))
+        }
+        _ => None,
+    }
+}
+
+/// Joins an nbformat multiline string, which may be a string or a list of lines.
+fn multiline(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Truncates overly long text outputs, keeping the beginning.
+fn truncate(text: &mut String) {
+    if text.len() > MAX_TEXT_LENGTH {
+        let mut end = MAX_TEXT_LENGTH;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("\n... [output truncated]");
+    }
+}
+
+/// Removes ANSI escape sequences, as found in IPython tracebacks.
+fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' && chars.peek() == Some(&'[') {
+            chars.next();
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Converts every `<table>` in an HTML fragment to a markdown table.
+///
+/// Text outside tables (such as pandas' "5 rows × 3 columns" caption) is kept
+/// as plain text. Returns `None` if the fragment contains no table.
+pub fn html_tables_to_markdown(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    if !lower.contains("<table") {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    let mut position = 0;
+    while let Some(start) = lower[position..].find("<table").map(|i| i + position) {
+        let end = lower[start..]
+            .find("</table>")
+            .map_or(html.len(), |i| start + i + "</table>".len());
+        let before = html_to_text(&html[position..start]);
+        if !before.is_empty() {
+            parts.push(before);
+        }
+        parts.push(table_to_markdown(&html[start..end]));
+        position = end;
+    }
+    let after = html_to_text(&html[position..]);
+    if !after.is_empty() {
+        parts.push(after);
+    }
+
+    Some(parts.join("\n\n"))
+}
+
+/// Converts a single HTML table to a markdown table.
+fn table_to_markdown(table: &str) -> String {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for row in find_elements(table, &["tr"]) {
+        let cells: Vec<String> = find_elements(row, &["th", "td"])
+            .into_iter()
+            .map(|cell| html_to_text(cell).replace('|', "\\|").replace('\n', " "))
+            .collect();
+        if !cells.is_empty() {
+            rows.push(cells);
+        }
+    }
+
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let mut lines = Vec::with_capacity(rows.len() + 1);
+    for (index, row) in rows.iter().enumerate() {
+        let mut row = row.clone();
+        row.resize(columns, String::new());
+        lines.push(format!("| {} |", row.join(" | ")));
+        if index == 0 {
+            lines.push(format!("|{}", " --- |".repeat(columns)));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Returns the inner HTML of every element with one of the given tags, in
+/// document order.
+///
+/// Elements are assumed not to nest, which holds for table rows and cells.
+fn find_elements<'a>(html: &'a str, tags: &[&str]) -> Vec<&'a str> {
+    let lower = html.to_ascii_lowercase();
+    let mut elements = Vec::new();
+
+    for tag in tags {
+        let open = format!("<{}", tag);
+        let close = format!("</{}>", tag);
+        let mut position = 0;
+
+        while let Some(start) = lower[position..].find(&open).map(|i| i + position) {
+            // Skip tags that merely share a prefix, such as <thead> for <th>.
+            let next = lower.as_bytes().get(start + open.len()).copied();
+            if !matches!(next, Some(b'>' | b' ' | b'\n' | b'\t' | b'/')) {
+                position = start + open.len();
+                continue;
+            }
+            let Some(content_start) = lower[start..].find('>').map(|i| start + i + 1) else {
+                break;
+            };
+            let content_end = lower[content_start..]
+                .find(&close)
+                .map_or(html.len(), |i| content_start + i);
+            elements.push((start, &html[content_start..content_end]));
+            position = content_end;
+        }
+    }
+
+    elements.sort_by_key(|(offset, _)| *offset);
+    elements.into_iter().map(|(_, inner)| inner).collect()
+}
+
+/// Strips tags, `<style>`/`<script>` blocks and common entities from HTML.
+fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let lower = html.to_ascii_lowercase();
+    let mut position = 0;
+
+    while position < html.len() {
+        let rest = &lower[position..];
+        if rest.starts_with("<style") || rest.starts_with("<script") {
+            let close = if rest.starts_with("<style") { "</style>" } else { "</script>" };
+            position = rest.find(close).map_or(html.len(), |i| position + i + close.len());
+        } else if rest.starts_with('<') {
+            if rest.starts_with("<br") || rest.starts_with("<p") || rest.starts_with("</p") {
+                text.push('\n');
+            }
+            position = rest.find('>').map_or(html.len(), |i| position + i + 1);
+        } else {
+            let end = rest.find('<').map_or(html.len(), |i| position + i);
+            text.push_str(&html[position..end]);
+            position = end;
+        }
+    }
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&times;", "×")
+        .replace("&amp;", "&");
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
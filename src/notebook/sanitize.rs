@@ -0,0 +1,207 @@
+//! Notebook sanitization and secret scanning.
+//!
+//! Used before pushing kernels so that stale outputs, widget state and
+//! credentials do not end up in (possibly public) notebooks on Kaggle.
+
+use super::{CellType, Notebook};
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// MIME type of Jupyter widget views in cell outputs.
+const WIDGET_VIEW_MIME: &str = "application/vnd.jupyter.widget-view+json";
+
+/// Secrets shorter than this are not matched literally, to avoid noise.
+const MIN_KNOWN_SECRET_LENGTH: usize = 8;
+
+/// Options controlling what [`sanitize_notebook`] removes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SanitizeOptions {
+    /// Remove all cell outputs and execution counts
+    pub strip_outputs: bool,
+    /// Remove saved widget state and widget outputs
+    pub remove_widget_state: bool,
+}
+
+/// Removes outputs and/or widget state from a notebook in place.
+pub fn sanitize_notebook(notebook: &mut Notebook, options: &SanitizeOptions) {
+    if options.remove_widget_state {
+        notebook.metadata.remove("widgets");
+    }
+
+    for cell in &mut notebook.cells {
+        if options.remove_widget_state {
+            cell.metadata.remove("widgets");
+        }
+        if cell.cell_type != CellType::Code {
+            continue;
+        }
+
+        if options.strip_outputs {
+            cell.extra.insert("outputs".to_string(), Value::Array(vec![]));
+            cell.extra.insert("execution_count".to_string(), Value::Null);
+        } else if options.remove_widget_state {
+            if let Some(Value::Array(outputs)) = cell.extra.get_mut("outputs") {
+                outputs.retain_mut(|output| {
+                    match output.get_mut("data").and_then(Value::as_object_mut) {
+                        Some(data) => {
+                            data.remove(WIDGET_VIEW_MIME);
+                            !data.is_empty()
+                        }
+                        None => true,
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// A potential secret found in kernel source or outputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretFinding {
+    /// What kind of secret was matched (e.g., "AWS access key")
+    pub kind: &'static str,
+    /// Where it was found (e.g., "cell 3 source, line 2")
+    pub location: String,
+    /// A marker standing in for the match, without any of its characters
+    pub preview: String,
+}
+
+impl std::fmt::Display for SecretFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {} ({})", self.kind, self.location, self.preview)
+    }
+}
+
+/// Patterns for well-known credential formats.
+static SECRET_PATTERNS: LazyLock<Vec<(&'static str, Regex)>> = LazyLock::new(|| {
+    [
+        ("AWS access key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+        (
+            "AWS secret key",
+            r#"(?i)aws_secret_access_key["']?\s*[=:]\s*["']?[A-Za-z0-9/+=]{40}"#,
+        ),
+        (
+            "Kaggle API key",
+            r#"(?i)(?:kaggle_key|"key")["']?\s*[=:]\s*["']?[0-9a-f]{32}\b"#,
+        ),
+        ("GitHub token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+        ("Hugging Face token", r"\bhf_[A-Za-z0-9]{30,}\b"),
+        ("API secret key", r"\bsk-[A-Za-z0-9_-]{20,}\b"),
+        ("Private key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+    ]
+    .into_iter()
+    .map(|(kind, pattern)| (kind, Regex::new(pattern).expect("valid secret pattern")))
+    .collect()
+});
+
+/// Scans text for credential-looking strings.
+///
+/// # Arguments
+///
+/// * `text` - The text to scan
+/// * `location` - Description of where the text comes from, used in findings
+/// * `known_secrets` - Exact secret values to look for, such as the active
+///   Kaggle API key
+///
+/// # Returns
+///
+/// Returns one finding per match, with line numbers relative to `text`.
+pub fn scan_text(text: &str, location: &str, known_secrets: &[String]) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let location = format!("{}, line {}", location, index + 1);
+
+        for secret in known_secrets {
+            if secret.len() >= MIN_KNOWN_SECRET_LENGTH && line.contains(secret.as_str()) {
+                let kind = "Configured Kaggle credential";
+                findings.push(SecretFinding {
+                    kind,
+                    location: location.clone(),
+                    preview: redact(kind),
+                });
+            }
+        }
+
+        for (kind, pattern) in SECRET_PATTERNS.iter() {
+            for found in pattern.find_iter(line) {
+                // Already reported as a configured credential.
+                if known_secrets.iter().any(|s| found.as_str().contains(s.as_str())) {
+                    continue;
+                }
+                findings.push(SecretFinding {
+                    kind,
+                    location: location.clone(),
+                    preview: redact(kind),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// Scans the sources and text outputs of every cell in a notebook.
+pub fn scan_notebook(notebook: &Notebook, known_secrets: &[String]) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+
+    for (index, cell) in notebook.cells.iter().enumerate() {
+        findings.extend(scan_text(
+            &cell.source,
+            &format!("cell {} source", index),
+            known_secrets,
+        ));
+
+        if let Some(Value::Array(outputs)) = cell.extra.get("outputs") {
+            let mut text = String::new();
+            for output in outputs {
+                collect_output_text(output, &mut text);
+            }
+            findings.extend(scan_text(
+                &text,
+                &format!("cell {} output", index),
+                known_secrets,
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Appends the textual content of an output, skipping binary image data.
+fn collect_output_text(output: &Value, text: &mut String) {
+    for key in ["text", "evalue", "traceback"] {
+        if let Some(value) = output.get(key) {
+            push_strings(value, text);
+        }
+    }
+    if let Some(data) = output.get("data").and_then(Value::as_object) {
+        for (mime_type, value) in data {
+            if !mime_type.starts_with("image/") {
+                push_strings(value, text);
+            }
+        }
+    }
+}
+
+/// Appends every string in `value`, each ending with a newline.
+fn push_strings(value: &Value, text: &mut String) {
+    match value {
+        Value::String(s) => {
+            text.push_str(s);
+            if !s.ends_with('\n') {
+                text.push('\n');
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|v| push_strings(v, text)),
+        Value::Object(map) => map.values().for_each(|v| push_strings(v, text)),
+        _ => {}
+    }
+}
+
+/// Returns the marker shown in place of a secret of a kind. No character of
+/// the secret itself is kept.
+fn redact(kind: &str) -> String {
+    format!("[REDACTED:{}]", kind)
+}
@@ -4,13 +4,18 @@
 //! Kaggle API functionality as tools that can be used by Claude AI and other
 //! MCP-compatible clients.
 
-use crate::client::KaggleClient;
-use crate::models::AuthenticationResponse;
+use crate::client::{KaggleClient, DEFAULT_PROFILE};
+use crate::models::{
+    AuthenticateOptions, AuthenticationResponse, CompetitionRef, KaggleConfig, KernelPushOptions,
+    KernelRef, KernelSourceFormat, ModelInstanceRef, ModelRef, ALLOW_ENDPOINT_OVERRIDES_ENV_VAR,
+};
+use crate::notebook::OutputContent;
 use rmcp::{
     model::*, schemars, service::RequestContext, tool, Error as McpError,
     RoleServer, ServerHandler,
 };
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -24,6 +29,18 @@ pub struct AuthenticateParams {
     pub kaggle_username: String,
     #[schemars(description = "Your Kaggle API key")]
     pub kaggle_key: String,
+    #[schemars(description = "Profile to save the credentials under when persist is set, e.g. a shared bot account (defaults to 'default', the top-level credentials in kaggle.json)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[schemars(description = "Save the credentials in the Kaggle config directory (~/.kaggle, or KAGGLE_CONFIG_DIR): in kaggle.json, or in credentials.enc once credentials are encrypted. Without it, they are only used until the server stops")]
+    #[serde(default)]
+    pub persist: bool,
+    #[schemars(description = "Overwrite saved credentials that belong to a different username. Only set this if the user explicitly asked to replace them")]
+    #[serde(default)]
+    pub force: bool,
+    #[schemars(description = "With persist, save to the encrypted credentials file instead of plaintext kaggle.json, moving any credentials already in kaggle.json there. Needs the KAGGLE_CREDENTIALS_PASSPHRASE environment variable. Once the encrypted file exists it is always used")]
+    #[serde(default)]
+    pub encrypt: bool,
 }
 
 /// Parameters for listing competitions.
@@ -48,6 +65,346 @@ pub struct CompetitionsListParams {
     #[schemars(description = "Page number for results paging")]
     #[serde(default = "default_page")]
     pub page: i32,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for submitting a kernel version to a code competition.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct CompetitionSubmitKernelParams {
+    #[schemars(description = "Competition URL suffix (e.g., 'titanic') or competition URL. Defaults to the configured competition")]
+    #[serde(default)]
+    pub competition: Option<CompetitionRef>,
+    
+    #[schemars(description = "Kernel reference in the format owner/kernel-slug, or notebook URL")]
+    pub kernel: KernelRef,
+    
+    #[schemars(description = "Kernel version number whose output should be submitted")]
+    pub version: i32,
+    
+    #[schemars(description = "Name of the kernel output file to submit")]
+    #[serde(default = "default_submission_file")]
+    pub file_name: String,
+    
+    #[schemars(description = "Message describing this submission")]
+    pub message: String,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for pulling a kernel.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct KernelPullParams {
+    #[schemars(description = "Kernel reference in the format owner/kernel-slug, or notebook URL")]
+    pub kernel: KernelRef,
+    
+    #[schemars(description = "Directory to write the kernel files to (defaults to the configured path, or the current directory)")]
+    #[serde(default)]
+    pub path: Option<String>,
+    
+    #[schemars(description = "Source file format (auto, notebook, script). 'script' converts notebooks to percent-format .py/.R files")]
+    #[serde(default = "default_kernel_format")]
+    pub format: String,
+    
+    #[schemars(description = "Whether to also write kernel-metadata.json")]
+    #[serde(default = "default_true")]
+    pub metadata: bool,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for pushing a kernel.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct KernelPushParams {
+    #[schemars(description = "Directory containing kernel-metadata.json and the code file. Percent-format scripts are converted to notebooks when kernel_type is notebook")]
+    pub folder: String,
+    
+    #[schemars(description = "Remove all cell outputs and execution counts before pushing")]
+    #[serde(default)]
+    pub strip_outputs: bool,
+    
+    #[schemars(description = "Remove saved widget state before pushing")]
+    #[serde(default)]
+    pub remove_widget_state: bool,
+    
+    #[schemars(description = "Push even if potential secrets (API keys, tokens) are found. Only set this after confirming the findings are false positives")]
+    #[serde(default)]
+    pub allow_secrets: bool,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for listing kernel versions.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct KernelVersionsParams {
+    #[schemars(description = "Kernel reference in the format owner/kernel-slug, or notebook URL")]
+    pub kernel: KernelRef,
+    
+    #[schemars(description = "Page number for results paging")]
+    #[serde(default = "default_page")]
+    pub page: i32,
+    
+    #[schemars(description = "Number of versions per page")]
+    #[serde(default = "default_page_size")]
+    pub page_size: i32,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for diffing two kernel versions.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct KernelDiffParams {
+    #[schemars(description = "Kernel reference in the format owner/kernel-slug, or notebook URL")]
+    pub kernel: KernelRef,
+    
+    #[schemars(description = "Older version number")]
+    pub from_version: i32,
+    
+    #[schemars(description = "Newer version number")]
+    pub to_version: i32,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for listing models.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelsListParams {
+    #[schemars(description = "Term(s) to search for")]
+    #[serde(default)]
+    pub search: String,
+    
+    #[schemars(description = "Only list models owned by this user or organization")]
+    #[serde(default)]
+    pub owner: Option<String>,
+    
+    #[schemars(description = "Sort by (hotness, downloadCount, voteCount, notebookCount, createTime)")]
+    #[serde(default = "default_model_sort_by")]
+    pub sort_by: String,
+    
+    #[schemars(description = "Number of models per page")]
+    #[serde(default = "default_page_size")]
+    pub page_size: i32,
+    
+    #[schemars(description = "Page token returned by a previous call, to fetch the next page")]
+    #[serde(default)]
+    pub page_token: Option<String>,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for getting a model.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelGetParams {
+    #[schemars(description = "Model reference in the format owner/model-slug, or model URL")]
+    pub model: ModelRef,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for tools that work on a directory with `model-metadata.json`.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelFolderParams {
+    #[schemars(description = "Directory containing (or to contain) model-metadata.json")]
+    #[serde(default = "default_path")]
+    pub folder: String,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for deleting a model.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelDeleteParams {
+    #[schemars(description = "Model reference in the format owner/model-slug, or model URL")]
+    pub model: ModelRef,
+    
+    #[schemars(description = "Must be true to confirm that the model and all its instances should be permanently deleted")]
+    #[serde(default)]
+    pub confirm: bool,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for tools that work on a directory with `model-instance-metadata.json`.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelInstanceFolderParams {
+    #[schemars(description = "Directory containing (or to contain) model-instance-metadata.json")]
+    #[serde(default = "default_path")]
+    pub folder: String,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for getting a model instance.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelInstanceGetParams {
+    #[schemars(description = "Model instance reference in the format owner/model-slug/framework/variation-slug, or instance URL")]
+    pub instance: ModelInstanceRef,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for deleting a model instance.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelInstanceDeleteParams {
+    #[schemars(description = "Model instance reference in the format owner/model-slug/framework/variation-slug, or instance URL")]
+    pub instance: ModelInstanceRef,
+    
+    #[schemars(description = "Must be true to confirm that the instance and all its versions should be permanently deleted")]
+    #[serde(default)]
+    pub confirm: bool,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for uploading a new model instance version.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelInstanceVersionCreateParams {
+    #[schemars(description = "Model instance reference in the format owner/model-slug/framework/variation-slug, or instance URL")]
+    pub instance: ModelInstanceRef,
+    
+    #[schemars(description = "Directory containing the files of the new version")]
+    #[serde(default = "default_path")]
+    pub folder: String,
+    
+    #[schemars(description = "Notes describing the new version")]
+    #[serde(default)]
+    pub version_notes: String,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for listing model instance versions.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelInstanceVersionsParams {
+    #[schemars(description = "Model instance reference in the format owner/model-slug/framework/variation-slug, or instance URL")]
+    pub instance: ModelInstanceRef,
+    
+    #[schemars(description = "Number of versions per page")]
+    #[serde(default = "default_page_size")]
+    pub page_size: i32,
+    
+    #[schemars(description = "Page token from a previous response")]
+    #[serde(default)]
+    pub page_token: Option<String>,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for downloading a model instance version.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ModelInstanceVersionDownloadParams {
+    #[schemars(description = "Model instance reference in the format owner/model-slug/framework/variation-slug[/version], or instance URL. Without a version, the latest version is downloaded")]
+    pub instance: ModelInstanceRef,
+    
+    #[schemars(description = "Directory to download to (defaults to the configured path, or the current directory)")]
+    #[serde(default)]
+    pub path: Option<String>,
+    
+    #[schemars(description = "Extract the downloaded archive and remove it")]
+    #[serde(default = "default_true")]
+    pub extract: bool,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Parameters for switching the active credential profile.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ProfileUseParams {
+    #[schemars(description = "Name of the profile to use for subsequent calls ('default' for the top-level credentials in kaggle.json)")]
+    pub name: String,
+}
+
+/// Parameters for describing the active account.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct WhoamiParams {
+    #[schemars(description = "Check the credentials against the Kaggle API before answering")]
+    #[serde(default)]
+    pub verify: bool,
+}
+
+/// Parameters for logging out.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct LogoutParams {
+    #[schemars(description = "Also delete the saved credentials (the active profile in kaggle.json or credentials.enc, or the access_token file, in the Kaggle config directory). Only set this if the user explicitly asked for it")]
+    #[serde(default)]
+    pub delete_persisted: bool,
+}
+
+/// Parameters for viewing the configuration.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ConfigViewParams {
+    #[schemars(description = "Show which layer (default, config_file, project_file, dot_env, environment or command_line) each value came from")]
+    #[serde(default)]
+    pub show_sources: bool,
+}
+
+/// Parameters for setting a configuration value.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ConfigSetParams {
+    #[schemars(description = "Configuration key, one of: competition (default competition slug), path (default download directory, absolute or starting with ~), proxy (http, https or socks5 proxy URL; only if the server allows endpoint overrides), ssl_ca_cert (PEM file with extra CA certificates to trust), api_base (Kaggle API base URL; only if the server allows endpoint overrides), strict_permissions (true to refuse credential files other users can read), max_attempts (attempts per API request including retries, 1 to 10), requests_per_minute (client-side request budget, 0 for no limit), cache (off, memory or disk)")]
+    pub name: String,
+    
+    #[schemars(description = "New value for the key")]
+    pub value: String,
+}
+
+/// Parameters for clearing a configuration value.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ConfigUnsetParams {
+    #[schemars(description = "Configuration key to clear: competition, path, proxy, ssl_ca_cert, api_base, strict_permissions, max_attempts, requests_per_minute or cache")]
+    pub name: String,
+}
+
+/// Parameters for viewing notebook outputs.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct NotebookOutputsParams {
+    #[schemars(description = "Kernel reference in the format owner/kernel-slug, or notebook URL, to pull the notebook from")]
+    #[serde(default)]
+    pub kernel: Option<KernelRef>,
+    
+    #[schemars(description = "Path to a local .ipynb file (alternative to kernel)")]
+    #[serde(default)]
+    pub path: Option<String>,
+    
+    #[schemars(description = "Whether to include image outputs such as plots")]
+    #[serde(default = "default_true")]
+    pub include_images: bool,
+    
+    #[schemars(description = "Credential profile to make this call with (defaults to the active profile)")]
+    #[serde(default)]
+    pub profile: Option<String>,
 }
 
 fn default_category() -> String {
@@ -66,6 +423,30 @@ fn default_page() -> i32 {
     1
 }
 
+fn default_model_sort_by() -> String {
+    "hotness".to_string()
+}
+
+fn default_page_size() -> i32 {
+    20
+}
+
+fn default_submission_file() -> String {
+    "submission.csv".to_string()
+}
+
+fn default_path() -> String {
+    ".".to_string()
+}
+
+fn default_kernel_format() -> String {
+    "auto".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
 /// The main MCP server implementation for Kaggle API integration.
 /// 
 /// This server provides tools for interacting with the Kaggle API through
@@ -91,21 +472,43 @@ pub struct KaggleMcpServer {
     client: Arc<RwLock<KaggleClient>>,
 }
 
+impl Default for KaggleMcpServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[tool(tool_box)]
 impl KaggleMcpServer {
     /// Creates a new instance of the Kaggle MCP server.
     /// 
     /// Initializes the server with a new Kaggle API client.
     pub fn new() -> Self {
+        Self::with_client(KaggleClient::new())
+    }
+
+    /// Creates a server that uses an already configured client.
+    pub fn with_client(client: KaggleClient) -> Self {
         Self {
-            client: Arc::new(RwLock::new(KaggleClient::new())),
+            client: Arc::new(RwLock::new(client)),
         }
     }
 
+    /// Returns the client to make a call with: one sharing the active
+    /// credentials, or one using `profile` if given.
+    async fn client_for(&self, profile: Option<&str>) -> std::result::Result<KaggleClient, McpError> {
+        let client = self.client.read().await;
+        client.for_profile(profile).await.map_err(|e| match e {
+            crate::models::Error::InvalidParameter(_) => McpError::invalid_params(e.to_string(), None),
+            e => McpError::internal_error(format!("Error loading profile: {}", e), None),
+        })
+    }
+
     /// Authenticates with the Kaggle API using the provided credentials.
     /// 
     /// This tool allows users to authenticate with their Kaggle username and API key.
-    /// The credentials can be obtained from the Kaggle account settings page.
+    /// The credentials can be obtained from the Kaggle account settings page. They
+    /// are only saved to disk when `persist` is set.
     /// 
     /// # Arguments
     /// 
@@ -115,27 +518,44 @@ impl KaggleMcpServer {
     /// 
     /// Returns a success message if authentication is successful, or an error
     /// if the credentials are invalid.
-    #[tool(description = "Authenticate with the Kaggle API using your username and API key")]
+    #[tool(description = "Authenticate with the Kaggle API using your username and API key. Credentials are kept in memory unless persist is set")]
     async fn authenticate(
         &self,
         #[tool(aggr)] params: AuthenticateParams,
     ) -> std::result::Result<CallToolResult, McpError> {
         let client = self.client.read().await;
         
+        let options = AuthenticateOptions {
+            profile: params.profile.clone(),
+            persist: params.persist,
+            force: params.force,
+            encrypt: params.encrypt,
+        };
         match client
-            .authenticate(params.kaggle_username.clone(), params.kaggle_key)
+            .authenticate_with(params.kaggle_username.clone(), params.kaggle_key, &options)
             .await
         {
             Ok(_) => {
+                let message = if params.persist {
+                    format!(
+                        "Successfully authenticated with Kaggle API; credentials saved to profile {}",
+                        params.profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+                    )
+                } else {
+                    "Successfully authenticated with Kaggle API; credentials were not saved".to_string()
+                };
                 let response = AuthenticationResponse {
                     success: true,
-                    message: "Successfully authenticated with Kaggle API".to_string(),
+                    message,
                     username: Some(params.kaggle_username),
                 };
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&response).unwrap(),
                 )]))
             }
+            Err(e @ crate::models::Error::InvalidParameter(_)) => {
+                Err(McpError::invalid_params(e.to_string(), None))
+            }
             Err(e) => Err(McpError::internal_error(e.to_string(), None)),
         }
     }
@@ -158,7 +578,7 @@ impl KaggleMcpServer {
         &self,
         #[tool(aggr)] params: CompetitionsListParams,
     ) -> std::result::Result<CallToolResult, McpError> {
-        let client = self.client.read().await;
+        let client = self.client_for(params.profile.as_deref()).await?;
         
         // Check if authenticated
         if !client.is_authenticated().await {
@@ -206,8 +626,1098 @@ impl KaggleMcpServer {
             )),
         }
     }
-}
 
+    /// Submits a kernel version's output to a code competition.
+    /// 
+    /// Code (notebook-only) competitions do not accept uploaded files; instead
+    /// a kernel version attached to the competition is submitted. The kernel
+    /// must be attached to the competition and the version must have
+    /// completed successfully.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Competition (or the configured default), kernel, version,
+    ///   output file and message
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the submission result message.
+    #[tool(description = "Submit a kernel version's output file to a code (notebook-only) competition")]
+    async fn competition_submit_kernel(
+        &self,
+        #[tool(aggr)] params: CompetitionSubmitKernelParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        let competition = client
+            .resolve_competition(params.competition.as_ref())
+            .await
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+
+        match client
+            .submit_kernel_version(
+                &competition,
+                &params.kernel,
+                params.version,
+                &params.file_name,
+                &params.message,
+            )
+            .await
+        {
+            Ok(response) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&response).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error submitting kernel: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Pulls a kernel's source code to a local directory.
+    /// 
+    /// Notebook kernels can be written as percent-format scripts (`.py` / `.R`)
+    /// so they can be reviewed in version control, and script kernels can be
+    /// written as notebooks.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Kernel pull parameters
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a JSON object with the paths of the written files.
+    #[tool(description = "Pull a kernel's source code, optionally converting notebooks to percent-format scripts")]
+    async fn kernel_pull(
+        &self,
+        #[tool(aggr)] params: KernelPullParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        let format: KernelSourceFormat = params
+            .format
+            .parse()
+            .map_err(|e: crate::models::Error| McpError::invalid_params(e.to_string(), None))?;
+
+        let path = client.download_path(params.path.as_deref()).await;
+        match client
+            .pull_kernel_to(&params.kernel, &path, format, params.metadata)
+            .await
+        {
+            Ok(pulled) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&pulled).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error pulling kernel: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Pushes a kernel from a local directory and starts a new run.
+    /// 
+    /// The directory must contain a `kernel-metadata.json` file. The code file
+    /// is converted to match the kernel type, optionally stripped of outputs
+    /// and widget state, and scanned for credentials before uploading. The
+    /// push is blocked if potential secrets are found, unless overridden.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Kernel push parameters
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the kernel reference, URL and new version number.
+    #[tool(description = "Push a kernel from a directory containing kernel-metadata.json, converting scripts to notebooks as needed. Blocks the push if credentials are found in code or outputs")]
+    async fn kernel_push(
+        &self,
+        #[tool(aggr)] params: KernelPushParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        let options = KernelPushOptions {
+            strip_outputs: params.strip_outputs,
+            remove_widget_state: params.remove_widget_state,
+            allow_secrets: params.allow_secrets,
+        };
+
+        match client.push_kernel_from(Path::new(&params.folder), &options).await {
+            Ok(response) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&response).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error pushing kernel: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Returns the executed cell outputs of a notebook as MCP content.
+    /// 
+    /// Text outputs are returned as text, `image/png` and `image/jpeg` outputs
+    /// as image content, and HTML tables (such as pandas DataFrames) are
+    /// converted to markdown tables.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Either a kernel reference or a local notebook path
+    /// 
+    /// # Returns
+    /// 
+    /// Returns one content item per output, each cell introduced by a short
+    /// text header.
+    #[tool(description = "Show the executed outputs of a notebook (text, plots as images, tables as markdown) from a kernel or a local .ipynb file")]
+    async fn notebook_outputs(
+        &self,
+        #[tool(aggr)] params: NotebookOutputsParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let source = match (params.kernel, params.path) {
+            (Some(kernel), None) => {
+                let client = self.client_for(params.profile.as_deref()).await?;
+                
+                // Check if authenticated
+                if !client.is_authenticated().await {
+                    return Err(McpError::internal_error(
+                        "Not authenticated. Please use the authenticate tool first.",
+                        None,
+                    ));
+                }
+
+                let pulled = client.pull_kernel(&kernel).await.map_err(|e| {
+                    McpError::internal_error(format!("Error pulling kernel: {}", e), None)
+                })?;
+                if pulled.blob.kernel_type != "notebook" {
+                    return Err(McpError::invalid_params(
+                        format!("Kernel {} is not a notebook", kernel),
+                        None,
+                    ));
+                }
+                pulled.blob.source
+            }
+            (None, Some(path)) => tokio::fs::read_to_string(&path).await.map_err(|e| {
+                McpError::invalid_params(format!("Cannot read {}: {}", path, e), None)
+            })?,
+            _ => {
+                return Err(McpError::invalid_params(
+                    "Specify exactly one of 'kernel' or 'path'",
+                    None,
+                ))
+            }
+        };
+
+        let notebook = crate::notebook::parse_notebook(&source)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+        let cells = crate::notebook::extract_outputs(&notebook, params.include_images);
+        if cells.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "The notebook has no outputs. It may not have been executed.",
+            )]));
+        }
+
+        let mut contents = Vec::new();
+        for cell in cells {
+            let header = match cell.execution_count {
+                Some(count) => format!("Cell {} [{}]:", cell.cell_index, count),
+                None => format!("Cell {}:", cell.cell_index),
+            };
+            contents.push(Content::text(header));
+            for output in cell.outputs {
+                contents.push(match output {
+                    OutputContent::Text(text) | OutputContent::Markdown(text) => {
+                        Content::text(text)
+                    }
+                    OutputContent::Image { data, mime_type } => Content::image(data, mime_type),
+                });
+            }
+        }
+        Ok(CallToolResult::success(contents))
+    }
+
+    /// Lists the versions of a kernel.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Kernel version listing parameters
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a JSON array of versions with their number, title, creation
+    /// date, run status and run time.
+    #[tool(description = "List a kernel's versions with number, date, status, run time and title")]
+    async fn kernel_versions(
+        &self,
+        #[tool(aggr)] params: KernelVersionsParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client
+            .list_kernel_versions(&params.kernel, params.page, params.page_size)
+            .await
+        {
+            Ok(versions) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&versions).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error listing kernel versions: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Shows what changed in a kernel's source between two versions.
+    /// 
+    /// Notebooks are converted to percent-format scripts before diffing so
+    /// the result is readable.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Kernel and the two version numbers to compare
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a unified diff of the two versions.
+    #[tool(description = "Show a unified diff of a kernel's source between two versions (notebooks are compared as scripts)")]
+    async fn kernel_diff(
+        &self,
+        #[tool(aggr)] params: KernelDiffParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client
+            .diff_kernel_versions(&params.kernel, params.from_version, params.to_version)
+            .await
+        {
+            Ok(diff) if diff.is_empty() => Ok(CallToolResult::success(vec![Content::text(
+                format!(
+                    "No source changes between v{} and v{}",
+                    params.from_version, params.to_version
+                ),
+            )])),
+            Ok(diff) => Ok(CallToolResult::success(vec![Content::text(diff)])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error diffing kernel versions: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Lists Kaggle models with search, owner and sort filters.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Model listing parameters
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a JSON object with the models on the page and the token for
+    /// the next page.
+    #[tool(description = "List Kaggle models with search, owner and sort filters")]
+    async fn models_list(
+        &self,
+        #[tool(aggr)] params: ModelsListParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client
+            .list_models(
+                &params.search,
+                params.owner.as_deref(),
+                &params.sort_by,
+                params.page_size,
+                params.page_token.as_deref(),
+            )
+            .await
+        {
+            Ok(response) => {
+                let models: Vec<serde_json::Value> = response
+                    .models
+                    .into_iter()
+                    .map(|model| {
+                        serde_json::json!({
+                            "ref": model.ref_,
+                            "title": model.title,
+                            "subtitle": model.subtitle,
+                            "author": model.author,
+                            "url": model.url,
+                            "downloadCount": model.download_count,
+                            "voteCount": model.vote_count,
+                            "publishTime": model.publish_time.map(|d| d.to_rfc3339()),
+                        })
+                    })
+                    .collect();
+                let result = serde_json::json!({
+                    "models": models,
+                    "nextPageToken": response.next_page_token.filter(|t| !t.is_empty()),
+                });
+                
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => Err(McpError::internal_error(
+                format!("Error listing models: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Gets the full details of a model.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The model reference
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the model with its description, license, tags, instances,
+    /// download and vote counts and publish time.
+    #[tool(description = "Get details of a Kaggle model (owner/model-slug) including its instances")]
+    async fn model_get(
+        &self,
+        #[tool(aggr)] params: ModelGetParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client.get_model(&params.model).await {
+            Ok(model) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&model).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error getting model: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Writes a `model-metadata.json` template to a directory.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The directory to write the template to
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the path of the written template.
+    #[tool(description = "Write a model-metadata.json template to a directory")]
+    async fn model_initialize_metadata(
+        &self,
+        #[tool(aggr)] params: ModelFolderParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+
+        match client.initialize_model_metadata(Path::new(&params.folder)).await {
+            Ok(path) => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Wrote model metadata template to {}. Fill in ownerSlug, title and slug, then use model_create_new.",
+                path.display()
+            ))])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error initializing model metadata: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Creates a new model from the `model-metadata.json` in a directory.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The directory containing `model-metadata.json`
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the reference and URL of the created model.
+    #[tool(description = "Create a new model from the model-metadata.json in a directory")]
+    async fn model_create_new(
+        &self,
+        #[tool(aggr)] params: ModelFolderParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client.create_model(Path::new(&params.folder)).await {
+            Ok(response) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&response).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error creating model: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Updates a model from the `model-metadata.json` in a directory.
+    /// 
+    /// Only the fields that differ from the current model are updated.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The directory containing `model-metadata.json`
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the reference and URL of the updated model.
+    #[tool(description = "Update a model from the model-metadata.json in a directory (only changed fields are sent)")]
+    async fn model_update(
+        &self,
+        #[tool(aggr)] params: ModelFolderParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client.update_model(Path::new(&params.folder)).await {
+            Ok(response) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&response).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error updating model: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Permanently deletes a model and all of its instances.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The model reference and an explicit confirmation
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a confirmation message.
+    #[tool(description = "Permanently delete a model and all its instances (requires confirm=true)")]
+    async fn model_delete(
+        &self,
+        #[tool(aggr)] params: ModelDeleteParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        if !params.confirm {
+            return Err(McpError::invalid_params(
+                "Deleting a model is permanent. Set confirm to true to proceed.",
+                None,
+            ));
+        }
+
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client.delete_model(&params.model).await {
+            Ok(_) => Ok(CallToolResult::success(vec![Content::text(
+                format!("Deleted model {}", params.model),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error deleting model: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Gets the details of a model instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The instance reference
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the instance with its framework, license, fine-tunable flag,
+    /// training data and overview/usage markdown.
+    #[tool(description = "Get details of a model instance (owner/model-slug/framework/variation-slug)")]
+    async fn model_instance_get(
+        &self,
+        #[tool(aggr)] params: ModelInstanceGetParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client.get_model_instance(&params.instance).await {
+            Ok(instance) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&instance).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error getting model instance: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Writes a `model-instance-metadata.json` template to a directory.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The directory to write the template to
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the path of the written template.
+    #[tool(description = "Write a model-instance-metadata.json template to a directory")]
+    async fn model_instance_initialize_metadata(
+        &self,
+        #[tool(aggr)] params: ModelInstanceFolderParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+
+        match client.initialize_model_instance_metadata(Path::new(&params.folder)).await {
+            Ok(path) => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Wrote model instance metadata template to {}. Fill in ownerSlug, modelSlug, instanceSlug and framework, then use model_instance_create.",
+                path.display()
+            ))])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error initializing model instance metadata: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Creates a new model instance from the `model-instance-metadata.json`
+    /// in a directory.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The directory containing `model-instance-metadata.json`
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the reference and URL of the created instance.
+    #[tool(description = "Create a new instance of an existing model from the model-instance-metadata.json in a directory")]
+    async fn model_instance_create(
+        &self,
+        #[tool(aggr)] params: ModelInstanceFolderParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client.create_model_instance(Path::new(&params.folder)).await {
+            Ok(response) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&response).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error creating model instance: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Updates a model instance from the `model-instance-metadata.json` in a
+    /// directory.
+    /// 
+    /// Only the fields that differ from the current instance are updated.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The directory containing `model-instance-metadata.json`
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the reference and URL of the updated instance.
+    #[tool(description = "Update a model instance from the model-instance-metadata.json in a directory (only changed fields are sent)")]
+    async fn model_instance_update(
+        &self,
+        #[tool(aggr)] params: ModelInstanceFolderParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client.update_model_instance(Path::new(&params.folder)).await {
+            Ok(response) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&response).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error updating model instance: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Permanently deletes a model instance and all of its versions.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The instance reference and an explicit confirmation
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a confirmation message.
+    #[tool(description = "Permanently delete a model instance and all its versions (requires confirm=true)")]
+    async fn model_instance_delete(
+        &self,
+        #[tool(aggr)] params: ModelInstanceDeleteParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        if !params.confirm {
+            return Err(McpError::invalid_params(
+                "Deleting a model instance is permanent. Set confirm to true to proceed.",
+                None,
+            ));
+        }
+
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client.delete_model_instance(&params.instance).await {
+            Ok(_) => Ok(CallToolResult::success(vec![Content::text(
+                format!("Deleted model instance {}", params.instance),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error deleting model instance: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Uploads the files in a directory as a new model instance version.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The instance reference, directory and version notes
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the reference and URL of the new version.
+    #[tool(description = "Upload the files in a directory as a new version of a model instance")]
+    async fn model_instance_version_create(
+        &self,
+        #[tool(aggr)] params: ModelInstanceVersionCreateParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client
+            .create_model_instance_version(&params.instance, Path::new(&params.folder), &params.version_notes)
+            .await
+        {
+            Ok(response) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&response).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error creating model instance version: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Lists the versions of a model instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The instance reference and paging options
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the versions with their numbers, notes and creation times.
+    #[tool(description = "List the versions of a model instance")]
+    async fn model_instance_versions(
+        &self,
+        #[tool(aggr)] params: ModelInstanceVersionsParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        match client
+            .list_model_instance_versions(&params.instance, params.page_size, params.page_token.as_deref())
+            .await
+        {
+            Ok(versions) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&versions).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error listing model instance versions: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Downloads a version of a model instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The instance reference, version, target directory and
+    ///   whether to extract the archive
+    /// 
+    /// # Returns
+    /// 
+    /// Returns where the files were written.
+    #[tool(description = "Download a specific (or the latest) version of a model instance, extracting it by default")]
+    async fn model_instance_version_download(
+        &self,
+        #[tool(aggr)] params: ModelInstanceVersionDownloadParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client_for(params.profile.as_deref()).await?;
+        
+        // Check if authenticated
+        if !client.is_authenticated().await {
+            return Err(McpError::internal_error(
+                "Not authenticated. Please use the authenticate tool first.",
+                None,
+            ));
+        }
+
+        let path = client.download_path(params.path.as_deref()).await;
+        match client
+            .download_model_instance_version(&params.instance, &path, params.extract)
+            .await
+        {
+            Ok(path) => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Downloaded {} to {}",
+                params.instance,
+                path.display()
+            ))])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error downloading model instance version: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Shows the current configuration.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Whether to include where each value came from
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the effective default competition, download path, proxy, CA
+    /// certificate file and API base URL, optionally with the layer each value came from.
+    #[tool(description = "View the configuration (default competition, download path, proxy, CA certificate file and API base URL); set show_sources to see which layer each value came from")]
+    async fn config_view(
+        &self,
+        #[tool(aggr)] params: ConfigViewParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client.read().await;
+
+        let text = if params.show_sources {
+            serde_json::to_string_pretty(&client.config_sources().await).unwrap()
+        } else {
+            serde_json::to_string_pretty(&client.config().await).unwrap()
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    /// Sets and persists a configuration value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The key and its new value
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the updated configuration.
+    #[tool(description = "Set a configuration value (competition, path, proxy, ssl_ca_cert, api_base, strict_permissions, max_attempts, requests_per_minute or cache); it is saved to kaggle.json in the Kaggle config directory (~/.kaggle, or KAGGLE_CONFIG_DIR if set)")]
+    async fn config_set(
+        &self,
+        #[tool(aggr)] params: ConfigSetParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client.read().await;
+
+        // A tool call could otherwise send credentials to any host.
+        let is_endpoint = KaggleConfig::ENDPOINT_KEYS.contains(&params.name.as_str());
+        if is_endpoint && !client.endpoint_overrides_allowed().await {
+            return Err(McpError::invalid_params(
+                format!(
+                    "{} decides where credentials are sent and cannot be set with config_set; \
+                     set it in kaggle.json, the environment or on the command line, or start \
+                     the server with {}=1",
+                    params.name, ALLOW_ENDPOINT_OVERRIDES_ENV_VAR
+                ),
+                None,
+            ));
+        }
+
+        match client.set_config(&params.name, &params.value).await {
+            Ok(config) => {
+                let mut contents = vec![Content::text(serde_json::to_string_pretty(&config).unwrap())];
+                if let Some(note) = override_note(&client.config_sources().await, &params.name) {
+                    contents.push(Content::text(note));
+                }
+                Ok(CallToolResult::success(contents))
+            }
+            Err(e @ crate::models::Error::InvalidParameter(_)) => {
+                Err(McpError::invalid_params(e.to_string(), None))
+            }
+            Err(e) => Err(McpError::internal_error(
+                format!("Error saving configuration: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Clears and persists a configuration value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The key to clear
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the updated configuration.
+    #[tool(description = "Clear a configuration value (competition, path, proxy, ssl_ca_cert, api_base, strict_permissions, max_attempts, requests_per_minute or cache)")]
+    async fn config_unset(
+        &self,
+        #[tool(aggr)] params: ConfigUnsetParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client.read().await;
+
+        match client.unset_config(&params.name).await {
+            Ok(config) => {
+                let mut contents = vec![Content::text(serde_json::to_string_pretty(&config).unwrap())];
+                if let Some(note) = override_note(&client.config_sources().await, &params.name) {
+                    contents.push(Content::text(note));
+                }
+                Ok(CallToolResult::success(contents))
+            }
+            Err(e @ crate::models::Error::InvalidParameter(_)) => {
+                Err(McpError::invalid_params(e.to_string(), None))
+            }
+            Err(e) => Err(McpError::internal_error(
+                format!("Error saving configuration: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Lists the credential profiles in `kaggle.json`.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns each profile's name, username and authentication scheme, and
+    /// which one is active. Keys and tokens are never included.
+    #[tool(description = "List the credential profiles (name, username, auth scheme and whether it is active)")]
+    async fn profile_list(&self) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client.read().await;
+
+        match client.list_profiles().await {
+            Ok(profiles) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&profiles).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error listing profiles: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Switches the credentials used for subsequent calls to a profile.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - The profile to use
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the profile's name, username and authentication scheme.
+    #[tool(description = "Switch to a credential profile for subsequent calls")]
+    async fn profile_use(
+        &self,
+        #[tool(aggr)] params: ProfileUseParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client.read().await;
+
+        match client.use_profile(&params.name).await {
+            Ok(profile) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&profile).unwrap(),
+            )])),
+            Err(e @ crate::models::Error::InvalidParameter(_)) => {
+                Err(McpError::invalid_params(e.to_string(), None))
+            }
+            Err(e) => Err(McpError::internal_error(
+                format!("Error switching profile: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Describes the account requests are made with.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Whether to verify the credentials first
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the active username, authentication scheme, credential source,
+    /// profile and whether the credentials have been verified.
+    #[tool(description = "Show the active Kaggle account: username, auth scheme, credential source (environment, access_token_file, config_file, encrypted_file, profile or tool_call), profile and whether it was verified")]
+    async fn whoami(
+        &self,
+        #[tool(aggr)] params: WhoamiParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client.read().await;
+
+        match client.whoami(params.verify).await {
+            Ok(identity) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&identity).unwrap(),
+            )])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error verifying credentials: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Forgets the active credentials.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Whether to also delete the saved credentials
+    /// 
+    /// # Returns
+    /// 
+    /// Returns a confirmation listing anything that was deleted.
+    #[tool(description = "Log out by clearing the active credentials from memory, optionally deleting the saved copy")]
+    async fn logout(
+        &self,
+        #[tool(aggr)] params: LogoutParams,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let client = self.client.read().await;
+
+        match client.logout(params.delete_persisted).await {
+            Ok(deleted) if deleted.is_empty() => Ok(CallToolResult::success(vec![Content::text(
+                "Logged out",
+            )])),
+            Ok(deleted) => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Logged out and deleted {}",
+                deleted.join(", ")
+            ))])),
+            Err(e) => Err(McpError::internal_error(
+                format!("Error deleting saved credentials: {}", e),
+                None,
+            )),
+        }
+    }
+}
+
+/// Explains that a key saved to the config file is overridden by a higher
+/// layer, so the change has no effect yet.
+fn override_note(resolved: &crate::models::ResolvedConfig, key: &str) -> Option<String> {
+    let source = resolved.source(key)?;
+    if source.layer > crate::models::ConfigLayer::ConfigFile {
+        Some(format!(
+            "Note: {} is saved, but the effective value comes from the {}",
+            key, source
+        ))
+    } else {
+        None
+    }
+}
+
+#[tool(tool_box)]
 impl ServerHandler for KaggleMcpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -1,3 +1,5 @@
+#![allow(clippy::module_inception)]
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
@@ -23,6 +25,10 @@ mod tests {
         let params = AuthenticateParams {
             kaggle_username: "test_user".to_string(),
             kaggle_key: "test_key".to_string(),
+            profile: None,
+            persist: false,
+            force: false,
+            encrypt: false,
         };
 
         let json = serde_json::to_value(&params).unwrap();
@@ -32,6 +38,14 @@ mod tests {
         let deserialized: AuthenticateParams = serde_json::from_value(json).unwrap();
         assert_eq!(deserialized.kaggle_username, params.kaggle_username);
         assert_eq!(deserialized.kaggle_key, params.kaggle_key);
+
+        // Credentials are only saved when asked to.
+        let minimal: AuthenticateParams = serde_json::from_value(
+            serde_json::json!({"kaggle_username": "u", "kaggle_key": "k"}),
+        )
+        .unwrap();
+        assert!(!minimal.persist);
+        assert!(!minimal.force);
     }
 
     #[tokio::test]
@@ -70,6 +84,7 @@ mod tests {
             group: "entered".to_string(),
             sort_by: "prize".to_string(),
             page: 2,
+            profile: None,
         };
 
         let json = serde_json::to_value(&params).unwrap();
@@ -89,6 +104,7 @@ mod tests {
             group: "general".to_string(),
             sort_by: "latestDeadline".to_string(),
             page: 1,
+            profile: None,
         };
 
         // Since the server isn't authenticated, this should fail
@@ -101,6 +117,155 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_kernel_pull_params_defaults() {
+        let params: KernelPullParams =
+            serde_json::from_value(serde_json::json!({"kernel": "owner/slug"})).unwrap();
+
+        assert_eq!(params.kernel.to_string(), "owner/slug");
+        assert_eq!(params.path, None);
+        assert_eq!(params.format, "auto");
+        assert!(params.metadata);
+    }
+
+    #[tokio::test]
+    async fn test_server_lists_tools() {
+        let tools = KaggleMcpServer::tool_box().list();
+        let names: Vec<_> = tools.iter().map(|tool| tool.name.as_ref()).collect();
+
+        assert!(names.contains(&"config_view"));
+        assert!(names.contains(&"authenticate"));
+        assert!(names.contains(&"competitions_list"));
+        assert!(names.contains(&"kernel_pull"));
+        assert!(names.contains(&"kernel_push"));
+    }
+
+    #[tokio::test]
+    async fn test_notebook_outputs_from_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("run.ipynb");
+        std::fs::write(
+            &path,
+            r#"{"cells": [{"cell_type": "code", "execution_count": 1, "metadata": {}, "source": "",
+                "outputs": [{"output_type": "display_data", "metadata": {}, "data": {"image/png": "AAAA"}}]}],
+                "metadata": {}, "nbformat": 4, "nbformat_minor": 4}"#,
+        )
+        .unwrap();
+
+        let server = create_test_server();
+        let params = NotebookOutputsParams {
+            kernel: None,
+            path: Some(path.to_string_lossy().to_string()),
+            include_images: true,
+            profile: None,
+        };
+        let result = server.notebook_outputs(params).await.unwrap();
+
+        assert_eq!(result.content.len(), 2);
+        assert_eq!(result.content[0].as_text().unwrap().text, "Cell 0 [1]:");
+        assert_eq!(result.content[1].as_image().unwrap().mime_type, "image/png");
+    }
+
+    #[tokio::test]
+    async fn test_notebook_outputs_requires_one_source() {
+        let server = create_test_server();
+        let params = NotebookOutputsParams {
+            kernel: None,
+            path: None,
+            include_images: true,
+            profile: None,
+        };
+        assert!(server.notebook_outputs(params).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_model_delete_requires_confirmation() {
+        let server = create_test_server();
+        let params = ModelDeleteParams {
+            model: "owner/model".parse().unwrap(),
+            confirm: false,
+            profile: None,
+        };
+
+        let result = server.model_delete(params).await;
+        assert!(result.unwrap_err().to_string().contains("confirm"));
+    }
+
+    #[tokio::test]
+    async fn test_model_instance_delete_requires_confirmation() {
+        let server = create_test_server();
+        let params = ModelInstanceDeleteParams {
+            instance: "owner/model/pyTorch/v1".parse().unwrap(),
+            confirm: false,
+            profile: None,
+        };
+
+        let result = server.model_instance_delete(params).await;
+        assert!(result.unwrap_err().to_string().contains("confirm"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_profile_is_rejected() {
+        let server = create_test_server();
+        let params: ModelGetParams =
+            serde_json::from_value(serde_json::json!({"model": "owner/model", "profile": "no-such-profile"}))
+                .unwrap();
+
+        let result = server.model_get(params).await;
+        assert!(result.unwrap_err().to_string().contains("Unknown profile"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_ref_is_rejected_when_parsing_params() {
+        let result: Result<KernelPullParams, _> =
+            serde_json::from_value(serde_json::json!({"kernel": "owner/slug/extra"}));
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("Invalid kernel reference"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_ref_params_schema_is_string() {
+        let schema = schemars::schema_for!(ModelInstanceGetParams);
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(json["properties"]["instance"]["type"], "string");
+    }
+
+    #[tokio::test]
+    async fn test_config_set_rejects_unknown_key() {
+        let server = create_test_server();
+        let params = ConfigSetParams {
+            name: "color".to_string(),
+            value: "blue".to_string(),
+        };
+
+        let result = server.config_set(params).await;
+        assert!(result.unwrap_err().to_string().contains("Unknown configuration key"));
+    }
+
+    #[tokio::test]
+    async fn test_config_set_refuses_endpoint_keys() {
+        let server = create_test_server();
+        for name in ["api_base", "proxy"] {
+            let params = ConfigSetParams {
+                name: name.to_string(),
+                value: "https://evil.example".to_string(),
+            };
+
+            let result = server.config_set(params).await;
+            assert!(result.unwrap_err().to_string().contains("decides where credentials are sent"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_competition_is_optional_in_schema() {
+        let schema = schemars::schema_for!(CompetitionSubmitKernelParams);
+        let json = serde_json::to_value(&schema).unwrap();
+        let required = json["required"].as_array().unwrap();
+
+        assert!(!required.contains(&serde_json::json!("competition")));
+        assert!(required.contains(&serde_json::json!("kernel")));
+    }
+
     // Integration tests would be better done with a full server setup
     // For now, we focus on unit testing the components
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(dead_code)]
 pub fn init_test_logger() {
     use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
     